@@ -0,0 +1,68 @@
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Paces the main loop to a target frame rate and measures how long frames take.
+pub struct FrameScheduler {
+    budget: Option<Duration>,
+    frame_start: Instant,
+    delta: Duration,
+    frame_time: Duration,
+}
+
+impl FrameScheduler {
+    /// A `target_fps` of 0 disables the sleep, e.g. when vsync already paces presentation.
+    pub fn new(target_fps: u32) -> Self {
+        let mut scheduler = Self {
+            budget: None,
+            frame_start: Instant::now(),
+            delta: Duration::ZERO,
+            frame_time: Duration::ZERO,
+        };
+        scheduler.set_target_fps(target_fps);
+        scheduler
+    }
+
+    pub fn set_target_fps(&mut self, target_fps: u32) {
+        self.budget = match target_fps {
+            0 => None,
+            fps => Some(Duration::from_secs(1) / fps),
+        };
+    }
+
+    /// Starts a new frame and returns the time elapsed since the previous one started.
+    pub fn begin_frame(&mut self) -> Duration {
+        let now = Instant::now();
+        self.delta = now - self.frame_start;
+        self.frame_start = now;
+        self.delta
+    }
+
+    /// Restarts the current frame after the loop blocked waiting for events. The wait counts
+    /// neither as frame time nor towards the delta of the next frame, so animations resuming
+    /// after an idle period do not jump ahead.
+    pub fn resume(&mut self) {
+        self.frame_start = Instant::now();
+    }
+
+    /// Records the work time of the current frame and sleeps for whatever is left of the budget.
+    pub fn end_frame(&mut self) {
+        self.frame_time = self.frame_start.elapsed();
+        if let Some(budget) = self.budget {
+            if let Some(remaining) = budget.checked_sub(self.frame_time) {
+                thread::sleep(remaining);
+            }
+        }
+    }
+
+    /// Time between the start of the previous frame and the current one.
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    /// Time the last finished frame spent working, excluding the pacing sleep.
+    pub fn frame_time(&self) -> Duration {
+        self.frame_time
+    }
+}
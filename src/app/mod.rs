@@ -0,0 +1,462 @@
+use std::{any::Any, collections::VecDeque, path::Path, time::Duration};
+
+use frame::FrameScheduler;
+use record::{Recorder, Recording, Replay};
+
+use sdl2::{
+    event::{Event, WindowEvent},
+    Sdl, VideoSubsystem,
+};
+
+use crate::{
+    window::{
+        self,
+        registry::{WindowRef, WindowRegistry},
+        session::{Session, SessionStorage},
+        MyWindow,
+    },
+    Action, WindowFactory,
+};
+
+pub mod frame;
+pub mod record;
+
+type StartupFn<S> = Box<dyn FnOnce(&mut App<S>) -> Result<(), String>>;
+type FrameFn<S> = Box<dyn FnMut(&mut App<S>)>;
+type ShutdownFn<S> = Box<dyn FnOnce(&mut App<S>)>;
+/// Handles one message type, giving the message back if it is of another type.
+type UpdateFn<S> = Box<dyn FnMut(&mut S, Box<dyn Any>) -> Result<Action, Box<dyn Any>>>;
+
+const DEFAULT_TARGET_FPS: u32 = 60;
+/// How long the loop blocks waiting for events while no window needs a repaint.
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Upper bound for messages produced by `update` in a single frame, guards against message loops.
+const MAX_MESSAGES_PER_FRAME: usize = 1024;
+
+/// Owns the SDL context, every open window and the application state and drives the event loop.
+/// The first window added is the main window, closing it quits the application.
+pub struct App<S = ()> {
+    sdl_context: Sdl,
+    video_subsystem: VideoSubsystem,
+    windows: WindowRegistry,
+    running: bool,
+    frames: FrameScheduler,
+    idle_timeout: Duration,
+    state: S,
+    on_startup: Option<StartupFn<S>>,
+    on_frame: Option<FrameFn<S>>,
+    on_shutdown: Option<ShutdownFn<S>>,
+    update: Vec<UpdateFn<S>>,
+    restorable: Vec<(String, WindowFactory)>,
+    recorder: Option<Recorder>,
+    replay: Option<Replay>,
+}
+
+impl App {
+    pub fn new() -> Result<Self, String> {
+        Self::with_state(())
+    }
+}
+
+impl<S: 'static> App<S> {
+    pub fn with_state(state: S) -> Result<Self, String> {
+        let sdl_context = window::init_sdl()?;
+        // add anti-aliasing to scaled textures
+        sdl2::hint::set("SDL_RENDER_SCALE_QUALITY", "1");
+        let video_subsystem = sdl_context.video()?;
+        Ok(Self {
+            sdl_context,
+            video_subsystem,
+            windows: WindowRegistry::new(),
+            running: false,
+            frames: FrameScheduler::new(DEFAULT_TARGET_FPS),
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+            state,
+            on_startup: None,
+            on_frame: None,
+            on_shutdown: None,
+            update: vec![],
+            restorable: vec![],
+            recorder: None,
+            replay: None,
+        })
+    }
+
+    /// Runs once before the first frame, e.g. to create the initial windows.
+    pub fn on_startup<F: 'static + FnOnce(&mut App<S>) -> Result<(), String>>(mut self, f: F) -> Self {
+        self.on_startup = Some(Box::new(f));
+        self
+    }
+
+    /// Runs every frame after events and actions have been handled, before the windows redraw.
+    pub fn on_frame<F: 'static + FnMut(&mut App<S>)>(mut self, f: F) -> Self {
+        self.on_frame = Some(Box::new(f));
+        self
+    }
+
+    /// Runs once after the loop has stopped, while the remaining windows are still open.
+    pub fn on_shutdown<F: 'static + FnOnce(&mut App<S>)>(mut self, f: F) -> Self {
+        self.on_shutdown = Some(Box::new(f));
+        self
+    }
+
+    /// Handles every `Action::Message` carrying an `M` after event dispatch.
+    /// The returned action is applied as if the window that sent the message had returned it.
+    /// Can be called once per message type.
+    pub fn update<M: 'static, F: 'static + FnMut(&mut S, M) -> Action>(mut self, mut f: F) -> Self {
+        self.update.push(Box::new(move |state, msg| {
+            let msg = msg.downcast::<M>()?;
+            Ok(f(state, *msg))
+        }));
+        self
+    }
+
+    /// Limits the loop to `fps` frames per second, 0 lets it run as fast as presenting allows.
+    pub fn target_fps(mut self, fps: u32) -> Self {
+        self.frames.set_target_fps(fps);
+        self
+    }
+
+    /// Longest time the loop sleeps waiting for events while nothing needs repainting.
+    /// `on_frame` keeps running at least this often.
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = timeout;
+        self
+    }
+
+    /// Time between the last two frames.
+    pub fn delta(&self) -> Duration {
+        self.frames.delta()
+    }
+
+    /// Time the last frame spent handling events and drawing.
+    pub fn frame_time(&self) -> Duration {
+        self.frames.frame_time()
+    }
+
+    /// Saves the geometry of persistent windows to `storage` and restores it on the next start,
+    /// also for persistent windows added before.
+    /// An unreadable session is reported and replaced by an empty one.
+    pub fn session<T: 'static + SessionStorage>(mut self, storage: T) -> Self {
+        match Session::load(storage) {
+            Ok(session) => self.windows.set_session(session),
+            Err(e) => eprintln!("Could not load session: {}", e),
+        }
+        self
+    }
+
+    /// Reopens the window registered under `key` at startup if it was open when the app last exited.
+    pub fn restore_window<F: 'static + FnOnce() -> Result<MyWindow, String>>(mut self, key: &str, factory: F) -> Self {
+        self.restorable.push((key.to_string(), Box::new(factory)));
+        self
+    }
+
+    /// Logs every event handled by the loop to `path`, see `Recorder` for the format.
+    pub fn record<P: AsRef<Path>>(mut self, path: P) -> Self {
+        match Recorder::create(path.as_ref()) {
+            Ok(recorder) => self.recorder = Some(recorder),
+            Err(e) => eprintln!("Could not start recording: {}", e),
+        }
+        self
+    }
+
+    /// Feeds the events recorded in `path` through the windows, `speed` times as fast as they
+    /// were recorded. Real mouse and keyboard input is ignored until the replay has finished.
+    /// Events go to the SDL window ids they were recorded with, which only match when the
+    /// windows are opened in the same order as in the recorded session.
+    pub fn replay<P: AsRef<Path>>(mut self, path: P, speed: f32) -> Self {
+        match Recording::load(path) {
+            Ok(recording) => self.replay = Some(Replay::new(recording, speed)),
+            Err(e) => eprintln!("Could not load recording: {}", e),
+        }
+        self
+    }
+
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    pub fn state_mut(&mut self) -> &mut S {
+        &mut self.state
+    }
+
+    pub fn video(&self) -> &VideoSubsystem {
+        &self.video_subsystem
+    }
+
+    /// Registers a window under `key`, the first window added becomes the main window.
+    pub fn add_window(&mut self, key: &str, window: MyWindow) -> u32 {
+        self.windows.insert_keyed(key, window)
+    }
+
+    pub fn windows(&self) -> &WindowRegistry {
+        &self.windows
+    }
+
+    pub fn windows_mut(&mut self) -> &mut WindowRegistry {
+        &mut self.windows
+    }
+
+    /// Stops the loop at the end of the current frame.
+    pub fn quit(&mut self) {
+        self.running = false;
+    }
+
+    pub fn run(mut self) -> Result<(), String> {
+        let mut event_pump = self.sdl_context.event_pump()?;
+
+        if let Some(on_startup) = self.on_startup.take() {
+            on_startup(&mut self)?;
+        }
+
+        for (key, factory) in std::mem::take(&mut self.restorable) {
+            if self.windows.session().is_some_and(|s| s.was_open(&key)) {
+                if let Err(e) = self.windows.create_if_not_exists(&key, factory) {
+                    eprintln!("Could not restore window \"{}\": {}", key, e);
+                }
+            }
+        }
+
+        if let Some(replay) = self.replay.as_mut() {
+            replay.start();
+        }
+
+        self.running = true;
+        self.frames.begin_frame();
+        while self.running {
+            let dt = self.frames.begin_frame().as_secs_f32();
+            let mut actions: Vec<(u32, Action)> = Vec::new();
+            if !self.windows.iter().any(|w| w.needs_redraw()) {
+                let timeout = match self.replay.as_ref().and_then(|r| r.until_next()) {
+                    Some(next) => next.min(self.idle_timeout),
+                    None => self.idle_timeout,
+                };
+                let event = event_pump.wait_event_timeout(timeout.as_millis() as u32);
+                self.frames.resume();
+                if let Some(event) = event {
+                    actions.append(&mut self.handle(event));
+                }
+            }
+            for event in event_pump.poll_iter() {
+                actions.append(&mut self.handle(event));
+            }
+            if let Some(mut replay) = self.replay.take() {
+                for event in replay.due() {
+                    actions.append(&mut self.dispatch(event));
+                }
+                if !replay.is_finished() {
+                    self.replay = Some(replay);
+                }
+            }
+
+            self.apply(actions);
+
+            if let Some(mut on_frame) = self.on_frame.take() {
+                on_frame(&mut self);
+                self.on_frame = Some(on_frame);
+            }
+
+            for window in self.windows.iter_mut() {
+                window.tick(dt);
+                window.update();
+            }
+
+            self.frames.end_frame();
+        }
+
+        if let Some(on_shutdown) = self.on_shutdown.take() {
+            on_shutdown(&mut self);
+        }
+        self.windows.close_all();
+
+        if let Some(mut recorder) = self.recorder.take() {
+            recorder.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Records and dispatches an event from SDL, dropping input while a replay is running.
+    fn handle(&mut self, event: Event) -> Vec<(u32, Action)> {
+        if let Some(recorder) = self.recorder.as_mut() {
+            if let Err(e) = recorder.record(&event) {
+                eprintln!("Stopped recording: {}", e);
+                self.recorder = None;
+            }
+        }
+        if self.replay.is_some() && window::is_input(&event) {
+            return vec![];
+        }
+        self.dispatch(event)
+    }
+
+    /// Hands the event to the window it belongs to, or to every window if it has no window id.
+    /// Returned actions are paired with the id of the window that produced them.
+    fn dispatch(&mut self, event: Event) -> Vec<(u32, Action)> {
+        let mut actions = vec![];
+        match event.get_window_id() {
+            Some(id) => {
+                if let Some(window) = self.windows.get_mut(id) {
+                    if window.is_active() {
+                        actions.extend(window.event(event.clone()).into_iter().map(|a| (id, a)));
+                    }
+                }
+            }
+            None => {
+                for window in self.windows.iter_mut().filter(|w| w.is_active()) {
+                    let id = window.get_id();
+                    actions.extend(window.event(event.clone()).into_iter().map(|a| (id, a)));
+                }
+            }
+        }
+
+        match event {
+            Event::Window {
+                win_event: WindowEvent::Close,
+                window_id,
+                ..
+            } => self.close_window(window_id),
+            // send the user to the dialog that blocks the window they tried to use
+            Event::MouseButtonDown { window_id, .. }
+            | Event::Window {
+                win_event: WindowEvent::FocusGained,
+                window_id,
+                ..
+            } => {
+                if let Some(modal) = self.windows.modal_child_of(window_id) {
+                    if let Some(window) = self.windows.get_mut(modal) {
+                        window.raise();
+                    }
+                }
+            }
+            Event::Quit { .. } => self.quit(),
+            _ => {}
+        }
+        actions
+    }
+
+    /// Applies window actions in order, then feeds the collected messages through `update`.
+    fn apply(&mut self, actions: Vec<(u32, Action)>) {
+        let mut messages = VecDeque::new();
+        for (source, action) in actions {
+            if let Err(e) = self.apply_action(source, action, &mut messages) {
+                eprintln!("Could not apply action: {}", e);
+            }
+        }
+
+        let mut handled = 0;
+        while let Some((source, msg)) = messages.pop_front() {
+            handled += 1;
+            if handled > MAX_MESSAGES_PER_FRAME {
+                eprintln!("Dropping {} messages, update keeps producing new ones", messages.len() + 1);
+                break;
+            }
+            let action = self.run_update(msg);
+            if let Err(e) = self.apply_action(source, action, &mut messages) {
+                eprintln!("Could not apply action: {}", e);
+            }
+        }
+    }
+
+    fn run_update(&mut self, mut msg: Box<dyn Any>) -> Action {
+        for update in self.update.iter_mut() {
+            match update(&mut self.state, msg) {
+                Ok(action) => return action,
+                Err(m) => msg = m,
+            }
+        }
+        eprintln!("No update handler for message {:?}", (*msg).type_id());
+        Action::None
+    }
+
+    fn apply_action(
+        &mut self,
+        source: u32,
+        action: Action,
+        messages: &mut VecDeque<(u32, Box<dyn Any>)>,
+    ) -> Result<(), String> {
+        match action {
+            Action::CreateWindowIfNotExists((key, factory)) => {
+                self.windows
+                    .create_if_not_exists(&key, factory)
+                    .map_err(|e| format!("window \"{}\": {}", key, e))?;
+            }
+            Action::CloseWindow(target) => {
+                if let Some(id) = self.windows.resolve(&target, source) {
+                    self.close_window(id);
+                }
+            }
+            Action::FocusWindow(target) => self.with_window(&target, source, |w| {
+                w.raise();
+                Ok(())
+            })?,
+            Action::MinimizeWindow(target) => self.with_window(&target, source, |w| {
+                w.minimize();
+                Ok(())
+            })?,
+            Action::MaximizeWindow(target) => self.with_window(&target, source, |w| {
+                w.maximize();
+                Ok(())
+            })?,
+            Action::RestoreWindow(target) => self.with_window(&target, source, |w| {
+                w.restore();
+                Ok(())
+            })?,
+            Action::SetWindowTitle((target, title)) => {
+                self.with_window(&target, source, |w| w.set_title(&title))?
+            }
+            Action::SetWindowSize((target, width, height)) => {
+                self.with_window(&target, source, |w| w.set_size(width, height))?
+            }
+            Action::SetWindowPosition((target, x, y)) => self.with_window(&target, source, |w| {
+                w.set_position(x, y);
+                Ok(())
+            })?,
+            Action::SetScaleFactor((target, scale)) => self.with_window(&target, source, |w| {
+                w.set_scale_factor(scale);
+                Ok(())
+            })?,
+            Action::ToggleFullscreen(target) => {
+                self.with_window(&target, source, |w| w.toggle_fullscreen())?
+            }
+            Action::UpdateWidget((target, id, f)) => self.with_window(&target, source, |w| {
+                {
+                    let mut widget = w.find_widget(&id).ok_or(format!("no widget \"{}\"", id))?;
+                    f(&mut *widget)?;
+                }
+                w.invalidate();
+                Ok(())
+            })?,
+            Action::Message(msg) => messages.push_back((source, msg)),
+            Action::Redraw(target) => self.with_window(&target, source, |w| {
+                w.invalidate();
+                Ok(())
+            })?,
+            Action::Quit => self.quit(),
+            Action::None => {}
+        }
+        Ok(())
+    }
+
+    /// Runs `f` on the targeted window, closed or unknown windows are silently ignored.
+    fn with_window<F: FnOnce(&mut MyWindow) -> Result<(), String>>(
+        &mut self,
+        target: &WindowRef,
+        source: u32,
+        f: F,
+    ) -> Result<(), String> {
+        match self.windows.resolve(target, source).and_then(|id| self.windows.get_mut(id)) {
+            Some(window) => f(window),
+            None => Ok(()),
+        }
+    }
+
+    /// Closing the main window stops the app, the remaining windows close at shutdown so the
+    /// session remembers them as open.
+    fn close_window(&mut self, id: u32) {
+        if self.windows.is_main_window(id) || (self.windows.remove(id).is_some() && self.windows.is_empty()) {
+            self.quit();
+        }
+    }
+}
@@ -0,0 +1,449 @@
+use std::{
+    collections::VecDeque,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use sdl2::{
+    event::{Event, WindowEvent},
+    keyboard::{Keycode, Mod, Scancode},
+    mouse::{MouseButton, MouseState, MouseWheelDirection},
+};
+
+/// Path to record the events of a session to, read by the demo.
+pub const RECORD_ENV: &str = "SDL_MULTIWINDOW_RECORD";
+/// Path of a recording to replay instead of taking input, read by the demo.
+pub const REPLAY_ENV: &str = "SDL_MULTIWINDOW_REPLAY";
+/// Replay speed factor, defaults to 1.
+pub const REPLAY_SPEED_ENV: &str = "SDL_MULTIWINDOW_REPLAY_SPEED";
+
+/// An event of a recording, `time` is in milliseconds since the recording started.
+#[derive(Clone, Debug)]
+pub struct RecordedEvent {
+    pub time: u32,
+    pub event: Event,
+}
+
+/// Appends every supported SDL event to a file, one event per line as tab separated
+/// `time window_id kind fields...`. Window ids are 0 for events without a window.
+/// Mouse, wheel, keyboard, text input, window and quit events are recorded, others are skipped.
+pub struct Recorder {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    start: Instant,
+}
+
+impl Recorder {
+    pub fn create<P: Into<PathBuf>>(path: P) -> Result<Self, String> {
+        let path = path.into();
+        let file = File::create(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(Self {
+            path,
+            writer: BufWriter::new(file),
+            start: Instant::now(),
+        })
+    }
+
+    pub fn record(&mut self, event: &Event) -> Result<(), String> {
+        let time = self.start.elapsed().as_millis() as u32;
+        match format_event(time, event) {
+            Some(line) => writeln!(self.writer, "{}", line).map_err(|e| format!("{}: {}", self.path.display(), e)),
+            None => Ok(()),
+        }
+    }
+
+    pub fn flush(&mut self) -> Result<(), String> {
+        self.writer.flush().map_err(|e| format!("{}: {}", self.path.display(), e))
+    }
+}
+
+/// Events read back from a file written by `Recorder`.
+#[derive(Clone, Debug, Default)]
+pub struct Recording {
+    events: Vec<RecordedEvent>,
+}
+
+impl Recording {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let data = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Self::parse(&data).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Unlike sessions a malformed line is an error, a replay with missing events is misleading.
+    pub fn parse(data: &str) -> Result<Self, String> {
+        let mut events = vec![];
+        for (i, line) in data.lines().enumerate().filter(|(_, l)| !l.is_empty()) {
+            match parse_line(line) {
+                Some(event) => events.push(event),
+                None => return Err(format!("line {}: malformed event \"{}\"", i + 1, line)),
+            }
+        }
+        Ok(Self { events })
+    }
+
+    pub fn events(&self) -> &[RecordedEvent] {
+        &self.events
+    }
+
+    /// Time of the last event in milliseconds.
+    pub fn duration(&self) -> u32 {
+        self.events.last().map_or(0, |e| e.time)
+    }
+}
+
+/// Hands out the events of a recording as they become due.
+/// `speed` scales the pace, 2.0 replays twice as fast and `f32::INFINITY` replays everything at once.
+pub struct Replay {
+    events: VecDeque<RecordedEvent>,
+    speed: f32,
+    start: Option<Instant>,
+}
+
+impl Replay {
+    pub fn new(recording: Recording, speed: f32) -> Self {
+        Self {
+            events: recording.events.into(),
+            speed,
+            start: None,
+        }
+    }
+
+    /// Starts the clock, `due` calls this on first use.
+    pub fn start(&mut self) {
+        self.start = Some(Instant::now());
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Removes and returns the events whose time has come.
+    pub fn due(&mut self) -> Vec<Event> {
+        let elapsed = self.start.get_or_insert_with(Instant::now).elapsed();
+        let mut due = vec![];
+        while let Some(next) = self.events.front() {
+            if self.at(next.time) > elapsed {
+                break;
+            }
+            due.extend(self.events.pop_front().map(|e| e.event));
+        }
+        due
+    }
+
+    /// Time left until the next event is due.
+    pub fn until_next(&self) -> Option<Duration> {
+        let elapsed = self.start.map_or(Duration::ZERO, |s| s.elapsed());
+        self.events.front().map(|e| self.at(e.time).saturating_sub(elapsed))
+    }
+
+    fn at(&self, time: u32) -> Duration {
+        if self.speed.is_infinite() {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f32(time as f32 / 1000. / self.speed.max(f32::EPSILON))
+        }
+    }
+}
+
+fn format_event(time: u32, event: &Event) -> Option<String> {
+    let window_id = event.get_window_id().unwrap_or(0);
+    let fields = match event {
+        Event::MouseMotion {
+            mousestate,
+            x,
+            y,
+            xrel,
+            yrel,
+            ..
+        } => format!("motion\t{}\t{}\t{}\t{}\t{}", x, y, xrel, yrel, mousestate.to_sdl_state()),
+        Event::MouseButtonDown {
+            mouse_btn, clicks, x, y, ..
+        } => format!("down\t{}\t{}\t{}\t{}", *mouse_btn as u8, clicks, x, y),
+        Event::MouseButtonUp {
+            mouse_btn, clicks, x, y, ..
+        } => format!("up\t{}\t{}\t{}\t{}", *mouse_btn as u8, clicks, x, y),
+        Event::MouseWheel {
+            x,
+            y,
+            direction,
+            precise_x,
+            precise_y,
+            ..
+        } => format!("wheel\t{}\t{}\t{}\t{}\t{}", x, y, precise_x, precise_y, direction.to_ll()),
+        Event::KeyDown {
+            keycode,
+            scancode,
+            keymod,
+            repeat,
+            ..
+        } => format!("keydown\t{}", format_key(*keycode, *scancode, *keymod, *repeat)),
+        Event::KeyUp {
+            keycode,
+            scancode,
+            keymod,
+            repeat,
+            ..
+        } => format!("keyup\t{}", format_key(*keycode, *scancode, *keymod, *repeat)),
+        Event::TextInput { text, .. } => format!("text\t{}", escape(text)),
+        Event::Window { win_event, .. } => {
+            let (name, data1, data2) = match *win_event {
+                WindowEvent::Shown => ("shown", 0, 0),
+                WindowEvent::Hidden => ("hidden", 0, 0),
+                WindowEvent::Exposed => ("exposed", 0, 0),
+                WindowEvent::Moved(x, y) => ("moved", x, y),
+                WindowEvent::Resized(w, h) => ("resized", w, h),
+                WindowEvent::SizeChanged(w, h) => ("size_changed", w, h),
+                WindowEvent::Minimized => ("minimized", 0, 0),
+                WindowEvent::Maximized => ("maximized", 0, 0),
+                WindowEvent::Restored => ("restored", 0, 0),
+                WindowEvent::Enter => ("enter", 0, 0),
+                WindowEvent::Leave => ("leave", 0, 0),
+                WindowEvent::FocusGained => ("focus_gained", 0, 0),
+                WindowEvent::FocusLost => ("focus_lost", 0, 0),
+                WindowEvent::Close => ("close", 0, 0),
+                WindowEvent::DisplayChanged(display) => ("display_changed", display, 0),
+                _ => return None,
+            };
+            format!("window\t{}\t{}\t{}", name, data1, data2)
+        }
+        Event::Quit { .. } => "quit".to_string(),
+        _ => return None,
+    };
+    Some(format!("{}\t{}\t{}", time, window_id, fields))
+}
+
+fn format_key(keycode: Option<Keycode>, scancode: Option<Scancode>, keymod: Mod, repeat: bool) -> String {
+    format!(
+        "{}\t{}\t{}\t{}",
+        keycode.map_or(0, |k| k as i32),
+        scancode.map_or(0, |s| s as i32),
+        keymod.bits(),
+        repeat as u8
+    )
+}
+
+fn parse_line(line: &str) -> Option<RecordedEvent> {
+    let fields: Vec<&str> = line.split('\t').collect();
+    if fields.len() < 3 {
+        return None;
+    }
+    let time: u32 = fields[0].parse().ok()?;
+    let window_id: u32 = fields[1].parse().ok()?;
+    let args = &fields[3..];
+    let int = |i: usize| args.get(i)?.parse::<i32>().ok();
+    let flag = |i: usize| match *args.get(i)? {
+        "0" => Some(false),
+        "1" => Some(true),
+        _ => None,
+    };
+    let event = match (fields[2], args.len()) {
+        ("motion", 5) => Event::MouseMotion {
+            timestamp: time,
+            window_id,
+            which: 0,
+            mousestate: MouseState::from_sdl_state(args[4].parse().ok()?),
+            x: int(0)?,
+            y: int(1)?,
+            xrel: int(2)?,
+            yrel: int(3)?,
+        },
+        ("down", 4) => Event::MouseButtonDown {
+            timestamp: time,
+            window_id,
+            which: 0,
+            mouse_btn: MouseButton::from_ll(args[0].parse().ok()?),
+            clicks: args[1].parse().ok()?,
+            x: int(2)?,
+            y: int(3)?,
+        },
+        ("up", 4) => Event::MouseButtonUp {
+            timestamp: time,
+            window_id,
+            which: 0,
+            mouse_btn: MouseButton::from_ll(args[0].parse().ok()?),
+            clicks: args[1].parse().ok()?,
+            x: int(2)?,
+            y: int(3)?,
+        },
+        ("wheel", 5) => Event::MouseWheel {
+            timestamp: time,
+            window_id,
+            which: 0,
+            x: int(0)?,
+            y: int(1)?,
+            precise_x: args[2].parse().ok()?,
+            precise_y: args[3].parse().ok()?,
+            direction: MouseWheelDirection::from_ll(args[4].parse().ok()?),
+        },
+        ("keydown", 4) => Event::KeyDown {
+            timestamp: time,
+            window_id,
+            keycode: Keycode::from_i32(int(0)?),
+            scancode: Scancode::from_i32(int(1)?),
+            keymod: Mod::from_bits_truncate(args[2].parse().ok()?),
+            repeat: flag(3)?,
+        },
+        ("keyup", 4) => Event::KeyUp {
+            timestamp: time,
+            window_id,
+            keycode: Keycode::from_i32(int(0)?),
+            scancode: Scancode::from_i32(int(1)?),
+            keymod: Mod::from_bits_truncate(args[2].parse().ok()?),
+            repeat: flag(3)?,
+        },
+        ("text", 1) => Event::TextInput {
+            timestamp: time,
+            window_id,
+            text: unescape(args[0]),
+        },
+        ("window", 3) => {
+            let (data1, data2) = (int(1)?, int(2)?);
+            let win_event = match args[0] {
+                "shown" => WindowEvent::Shown,
+                "hidden" => WindowEvent::Hidden,
+                "exposed" => WindowEvent::Exposed,
+                "moved" => WindowEvent::Moved(data1, data2),
+                "resized" => WindowEvent::Resized(data1, data2),
+                "size_changed" => WindowEvent::SizeChanged(data1, data2),
+                "minimized" => WindowEvent::Minimized,
+                "maximized" => WindowEvent::Maximized,
+                "restored" => WindowEvent::Restored,
+                "enter" => WindowEvent::Enter,
+                "leave" => WindowEvent::Leave,
+                "focus_gained" => WindowEvent::FocusGained,
+                "focus_lost" => WindowEvent::FocusLost,
+                "close" => WindowEvent::Close,
+                "display_changed" => WindowEvent::DisplayChanged(data1),
+                _ => return None,
+            };
+            Event::Window {
+                timestamp: time,
+                window_id,
+                win_event,
+            }
+        }
+        ("quit", 0) => Event::Quit { timestamp: time },
+        _ => return None,
+    };
+    Some(RecordedEvent { time, event })
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n")
+}
+
+fn unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => result.push('\t'),
+            Some('n') => result.push('\n'),
+            Some(c) => result.push(c),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_and_parse_round_trip() {
+        let events = vec![
+            Event::MouseMotion {
+                timestamp: 10,
+                window_id: 2,
+                which: 0,
+                mousestate: MouseState::from_sdl_state(1),
+                x: 40,
+                y: -3,
+                xrel: 5,
+                yrel: -7,
+            },
+            Event::MouseButtonDown {
+                timestamp: 20,
+                window_id: 2,
+                which: 0,
+                mouse_btn: MouseButton::Left,
+                clicks: 2,
+                x: 1,
+                y: 2,
+            },
+            Event::MouseButtonUp {
+                timestamp: 21,
+                window_id: 2,
+                which: 0,
+                mouse_btn: MouseButton::Right,
+                clicks: 1,
+                x: 1,
+                y: 2,
+            },
+            Event::MouseWheel {
+                timestamp: 30,
+                window_id: 3,
+                which: 0,
+                x: 0,
+                y: -1,
+                direction: MouseWheelDirection::Flipped,
+                precise_x: 0.25,
+                precise_y: -1.5,
+            },
+            Event::KeyDown {
+                timestamp: 40,
+                window_id: 3,
+                keycode: Some(Keycode::A),
+                scancode: Some(Scancode::A),
+                keymod: Mod::LSHIFTMOD | Mod::LCTRLMOD,
+                repeat: true,
+            },
+            Event::KeyUp {
+                timestamp: 41,
+                window_id: 3,
+                keycode: None,
+                scancode: None,
+                keymod: Mod::NOMOD,
+                repeat: false,
+            },
+            Event::TextInput {
+                timestamp: 50,
+                window_id: 3,
+                text: "a\tb\\c\n".to_string(),
+            },
+            Event::Window {
+                timestamp: 60,
+                window_id: 1,
+                win_event: WindowEvent::Resized(800, 600),
+            },
+            Event::Window {
+                timestamp: 61,
+                window_id: 1,
+                win_event: WindowEvent::Close,
+            },
+            Event::Quit { timestamp: 70 },
+        ];
+        for event in events {
+            let time = event.get_timestamp();
+            let line = format_event(time, &event).unwrap();
+            let parsed = parse_line(&line).unwrap_or_else(|| panic!("could not parse \"{}\"", line));
+            assert_eq!(parsed.time, time);
+            assert_eq!(parsed.event, event, "{}", line);
+        }
+    }
+
+    #[test]
+    fn skips_unsupported_events() {
+        let event = Event::AppTerminating { timestamp: 0 };
+        assert!(format_event(0, &event).is_none());
+        assert!(parse_line("0\t0\tunknown").is_none());
+    }
+}
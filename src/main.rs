@@ -1,25 +1,36 @@
 extern crate sdl2;
 
 use lazy_static::lazy_static;
-use sdl2::event::Event;
 use sdl2::pixels::Color;
 use sdl2::rect::Rect;
 use sdl2::render::Canvas;
 use sdl2::ttf::Sdl2TtfContext;
 use sdl2::video::Window;
+use sdl2::VideoSubsystem;
 use shapes::rounded_rect::RoundedRect;
 use utils::style::{FontStyle, TextAlign, Style, Params};
+use std::any::Any;
+use std::env;
 use std::cell::{RefCell, RefMut};
 use std::rc::Rc;
-use std::time::{Duration, SystemTime};
-use widgets::button::Button;
+use widgets::flex::Column;
 use widgets::list::List;
-use widgets::scrollview::ScrollView;
 use widgets::Widget;
-use window::MyWindow;
+use window::builder::WindowBuilder;
+use window::session::FileStorage;
+use window::{LayoutPolicy, MyWindow};
+use window::registry::WindowRef;
+use app::{
+    record::{RECORD_ENV, REPLAY_ENV, REPLAY_SPEED_ENV},
+    App,
+};
+use state::Signal;
+use widgets::text::Text;
 
 type CanvasCell = Rc<RefCell<Canvas<Window>>>;
 type DrawFn = Box<dyn FnMut(CanvasCell, RefMut<Vec<Box<dyn Widget>>>)>;
+type WindowFactory = Box<dyn FnOnce() -> Result<MyWindow, String>>;
+type WidgetFn = Box<dyn FnOnce(&mut dyn Widget) -> Result<(), String>>;
 
 macro_rules! add_new_to_zero {
     ($struct_name:ident, $($arg_name:ident : $arg_type:ty),*) => {
@@ -52,10 +63,56 @@ trait CustomCanvas {
 }
 
 pub enum Action {
-    CreateWindowIfNotExists((u32, MyWindow)),
+    /// Opens the window built by the factory unless a window with that key is already open,
+    /// in which case the existing one is raised and focused instead.
+    CreateWindowIfNotExists((String, WindowFactory)),
+    CloseWindow(WindowRef),
+    /// Raises the window and gives it input focus.
+    FocusWindow(WindowRef),
+    MinimizeWindow(WindowRef),
+    MaximizeWindow(WindowRef),
+    RestoreWindow(WindowRef),
+    SetWindowTitle((WindowRef, String)),
+    SetWindowSize((WindowRef, u32, u32)),
+    SetWindowPosition((WindowRef, i32, i32)),
+    /// Changes the user scale factor of the window and lays it out again.
+    SetScaleFactor((WindowRef, f32)),
+    ToggleFullscreen(WindowRef),
+    /// Repaints the window on the next frame, e.g. after `update` changed what it shows.
+    Redraw(WindowRef),
+    /// Runs the function on the widget with the given id in the targeted window, which is
+    /// repainted afterwards. Build it with `Action::update_widget` to get the concrete type.
+    UpdateWidget((WindowRef, String, WidgetFn)),
+    /// Application specific message, handed to the `update` functions registered on `App`.
+    Message(Box<dyn Any>),
+    Quit,
     None,
 }
 
+impl Action {
+    pub fn message<M: 'static>(msg: M) -> Self {
+        Action::Message(Box::new(msg))
+    }
+
+    pub fn update_widget<T: Widget + 'static, F: FnOnce(&mut T) + 'static>(window: WindowRef, id: &str, f: F) -> Self {
+        let name = id.to_string();
+        let update = move |widget: &mut dyn Widget| match widget.as_any_mut().downcast_mut::<T>() {
+            Some(widget) => {
+                f(widget);
+                Ok(())
+            }
+            None => Err(format!("widget \"{}\" is not a {}", name, std::any::type_name::<T>())),
+        };
+        Action::UpdateWidget((window, id.to_string(), Box::new(update)))
+    }
+}
+
+#[macro_use]
+mod ui;
+mod app;
+mod state;
+#[cfg(test)]
+mod testing;
 mod widgets;
 mod window;
 mod shapes;
@@ -66,22 +123,29 @@ lazy_static! {
     // static ref SDL_CONTEXT: Sdl = sdl2::init().unwrap();
 }
 
-fn main() -> Result<(), String> {
-    // set sdl2 hint to add anti-aliasing
+enum Msg {
+    Clicked,
+}
 
-    let sdl_context = sdl2::init()?;
-    sdl2::hint::set("SDL_RENDER_SCALE_QUALITY", "1");
-    let video_subsystem = sdl_context.video()?;
-    // let ttf_context = Rc::new(RefCell::new(sdl2::ttf::init().map_err(|e| e.to_string())?));
+struct State {
+    clicks: Signal<u32>,
+}
 
-    let mut event_pump = sdl_context.event_pump()?;
+fn main() -> Result<(), String> {
+    let clicks = Signal::new(0);
+    let mut app = App::with_state(State { clicks: clicks.clone() })?.update(|state: &mut State, msg: Msg| match msg {
+        Msg::Clicked => {
+            state.clicks.update(|c| *c += 1);
+            Action::SetWindowTitle((WindowRef::Current, format!("Window 1 ({} clicks)", state.clicks.get())))
+        }
+    });
+    let video_subsystem = app.video().clone();
+    // let ttf_context = Rc::new(RefCell::new(sdl2::ttf::init().map_err(|e| e.to_string())?));
 
-    let mut main_window = MyWindow::create(
-        &video_subsystem,
-        "Window 1",
-        800,
-        600,
-        move |canvas, mut widgets| {
+    let mut main_window = WindowBuilder::new("Window 1", 800, 600)
+        .high_dpi()
+        .persistent()
+        .build(&video_subsystem, move |canvas, mut widgets| {
             let mut c = canvas.borrow_mut();
             c.set_draw_color(Color::RGB(0, 0, 0));
             c.clear();
@@ -93,132 +157,97 @@ fn main() -> Result<(), String> {
             }
 
             c.present();
-        },
-    );
+        })?;
 
-    let windows = Rc::new(RefCell::new(vec![]));
+    main_window.add_widget(Box::new(
+        Text::new(10, 100, "", Style::new().text_color(Color::WHITE)).bind(&clicks, |c| format!("Clicks: {}", c)),
+    ));
 
+    let main_id = main_window.get_id();
+    let debug_video = video_subsystem.clone();
+    let debug_clicks = clicks.clone();
     let on_click = move || {
-        // let mut w_cb = w_c.borrow_mut();
-                let mut debug_win = MyWindow::create(
-                    &video_subsystem,
-                    "Second Window",
-                    400,
-                    800,
-                    move |canvas, mut widgets| {
-                        let mut c = canvas.borrow_mut();
-                        c.set_draw_color(Color::RGB(0, 0, 0));
-                        c.clear();
-
-                        for widget in widgets.iter_mut() {
-                            widget.draw(&mut c);
-                        }
-
-                        c.present();
-                    },
-                );
-
-                let mut lv = List::new(0, 100, 200, 600);
-
-                for i in 0..4000 {
-                    lv = lv.add_text(format!("Text {} \t lol", i).as_str());
-                }
-
-                debug_win.add_widget(Box::new(ScrollView::new(
-                    Box::new(lv),
-                    0,
-                    0,
-                    400,
-                    800,
-                )));
-
-                Action::CreateWindowIfNotExists((1, debug_win))
+        let video_subsystem = debug_video.clone();
+        let clicks = debug_clicks.clone();
+        let factory = move || debug_window(&video_subsystem, main_id, &clicks);
+        Action::CreateWindowIfNotExists(("debug".to_string(), Box::new(factory)))
     };
 
-    main_window.add_widget(Box::new(Button::new(
-        10,
-        10,
-        200,
-        20,
-        "Hello Rust!",
-        Box::new(on_click),
-        Style::new().background_color(Color::RGB(0, 0, 160)).border_radius(20).font_style(FontStyle::Normal).text_align(TextAlign::Right).text_color(Color::RED).font_size(20),
-    )));
-
-    main_window.add_widget(Box::new(Button::new(
-        10,
-        40,
-        200,
-        20,
-        "Hello Rust!",
-        Box::new(|| Action::None),
-        Style::new().background_color(Params::Hover(Color::RGB(160, 160, 160))).font_style(FontStyle::Bold).text_align(TextAlign::Center).text_color(Color::BLACK),
-    )));
-
-    main_window.add_widget(Box::new(Button::new(
-        10,
-        70,
-        200,
-        20,
-        "Hello Rust!",
-        Box::new(|| Action::None),
-        Style::new().background_color(Color::RGB(160, 0, 160)).font_style(FontStyle::Bold).text_align(TextAlign::Left).text_color(Color::BLACK).font_size(10),
-    )));
+    // the buttons keep their own size, the column only positions them
+    main_window.add_widget(Box::new(ui! {
+        Column(spacing = 10).rect(10, 10, 200, 80) {
+            Button(
+                "Hello Rust!",
+                on_click = on_click,
+                rect = (0, 0, 200, 20),
+                style = .background_color(Color::RGB(0, 0, 160)).border_radius(20).font_style(FontStyle::Normal).text_align(TextAlign::Right).text_color(Color::RED).font_size(20)
+            ),
+            Button(
+                "Hello Rust!",
+                on_click = || Action::message(Msg::Clicked),
+                rect = (0, 0, 200, 20),
+                style = .background_color(Params::Hover(Color::RGB(160, 160, 160))).font_style(FontStyle::Bold).text_align(TextAlign::Center).text_color(Color::BLACK)
+            ),
+            Button(
+                "Close second window",
+                on_click = || Action::CloseWindow(WindowRef::Key("debug".to_string())),
+                rect = (0, 0, 200, 20),
+                style = .background_color(Color::RGB(160, 0, 160)).font_style(FontStyle::Bold).text_align(TextAlign::Left).text_color(Color::BLACK).font_size(10)
+            ),
+        }
+    }));
 
     // main_window.add_widget(Box::new(Circle::new(100, 100, 5, Color::RGB(255, 255, 255))));
 
-    windows.borrow_mut().push(main_window);
+    // attached first so the persistent main window is restored as it is added
+    app = app.session(FileStorage::new(env::temp_dir().join("sdl_multiwindow_session.txt")));
+    app.add_window("main", main_window);
 
-    'running: loop {
-        let _now = SystemTime::now();
-        let mut actions: Vec<Action> = Vec::new();
-        for event in event_pump.poll_iter() {
-            for window in windows.borrow_mut().iter_mut() {
-                if window.is_active() {
-                    actions.append(&mut window.event(event.clone()));
-                }
-            }
-            match event {
-                Event::Window {
-                    win_event: sdl2::event::WindowEvent::Close,
-                    window_id: id,
-                    ..
-                } => {
-                    let mut bw = windows.borrow_mut();
-                    if let Some(entry_pos) = bw.iter().position(|w| w.get_id() == id) {
-                        let entry = bw.get(entry_pos).unwrap();
-                        drop(entry.get_canvas());
-                        bw.remove(entry_pos);
-                        if bw.is_empty() || entry_pos == 0 {
-                            break 'running;
-                        }
-                    }
-                }
-                Event::Quit { .. } => {
-                    break 'running;
-                }
-                _ => {}
-            }
-        }
+    if let Ok(path) = env::var(RECORD_ENV) {
+        app = app.record(path);
+    }
+    if let Ok(path) = env::var(REPLAY_ENV) {
+        let speed = env::var(REPLAY_SPEED_ENV).ok().and_then(|s| s.parse().ok()).unwrap_or(1.);
+        app = app.replay(path, speed);
+    }
+
+    app.restore_window("debug", move || debug_window(&video_subsystem, main_id, &clicks))
+        .run()
+}
+
+fn debug_window(video_subsystem: &VideoSubsystem, parent: u32, clicks: &Signal<u32>) -> Result<MyWindow, String> {
+    let mut debug_win = WindowBuilder::new("Second Window", 400, 800)
+        .resizable()
+        .high_dpi()
+        .parent(parent)
+        .min_size(200, 200)
+        .persistent()
+        .build(video_subsystem, move |canvas, mut widgets| {
+            let mut c = canvas.borrow_mut();
+            c.set_draw_color(Color::RGB(0, 0, 0));
+            c.clear();
 
-        for action in actions {
-            match action {
-                Action::CreateWindowIfNotExists((i, win)) => windows.borrow_mut().insert(i as usize, win),
-                Action::None => {}
+            for widget in widgets.iter_mut() {
+                widget.draw(&mut c);
             }
-        }
 
-        for window in windows.borrow_mut().iter_mut() {
-            window.update();
-        }
+            c.present();
+        })?;
+    debug_win.set_layout_policy(LayoutPolicy::Fill);
 
-        ::std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 60));
+    let mut lv = List::new(0, 100, 200, 600).add_widget(Box::new(
+        Text::new_to_zero("", Style::new()).bind(clicks, |c| format!("Clicks in Window 1: {}", c)),
+    ));
 
-        /* match (now.elapsed()) {
-            Ok(x) => println!("Elapsed time: {}", x.as_millis()),
-            Err(_) => {},
-        } */
+    for i in 0..4000 {
+        lv = lv.add_text(format!("Text {} \t lol", i).as_str());
     }
 
-    Ok(())
+    debug_win.add_widget(Box::new(ui! {
+        ScrollView(rect = (0, 0, 400, 800)) {
+            Widget(lv)
+        }
+    }));
+
+    Ok(debug_win)
 }
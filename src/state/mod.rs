@@ -0,0 +1,111 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+type Observer<T> = Box<dyn Fn(&T)>;
+
+struct SignalInner<T> {
+    value: RefCell<T>,
+    version: Cell<u64>,
+    observers: RefCell<Vec<Observer<T>>>,
+}
+
+/// A shared value that can be observed from any window. Clones refer to the same value.
+/// Widgets bound to a signal (see `Text::bind`) become dirty whenever it changes, so every
+/// window showing it repaints on the next frame.
+pub struct Signal<T> {
+    inner: Rc<SignalInner<T>>,
+}
+
+impl<T> Clone for Signal<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: 'static> Signal<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            inner: Rc::new(SignalInner {
+                value: RefCell::new(value),
+                version: Cell::new(0),
+                observers: RefCell::new(vec![]),
+            }),
+        }
+    }
+
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.inner.value.borrow().clone()
+    }
+
+    /// Reads the value without cloning it.
+    pub fn with<R, F: FnOnce(&T) -> R>(&self, f: F) -> R {
+        f(&self.inner.value.borrow())
+    }
+
+    pub fn set(&self, value: T) {
+        *self.inner.value.borrow_mut() = value;
+        self.changed();
+    }
+
+    /// Modifies the value in place and notifies observers once.
+    pub fn update<F: FnOnce(&mut T)>(&self, f: F) {
+        f(&mut self.inner.value.borrow_mut());
+        self.changed();
+    }
+
+    /// Incremented on every change, lets widgets detect changes by polling.
+    pub fn version(&self) -> u64 {
+        self.inner.version.get()
+    }
+
+    /// Calls `f` with the new value after every change.
+    /// Observers must not set the signal they are observing.
+    pub fn subscribe<F: 'static + Fn(&T)>(&self, f: F) {
+        self.inner.observers.borrow_mut().push(Box::new(f));
+    }
+
+    fn changed(&self) {
+        self.inner.version.set(self.inner.version.get() + 1);
+        let value = self.inner.value.borrow();
+        for observer in self.inner.observers.borrow().iter() {
+            observer(&value);
+        }
+    }
+}
+
+/// Type erased view of a signal that renders its value to text, used by bound widgets.
+#[derive(Clone)]
+pub struct Binding {
+    version: Rc<dyn Fn() -> u64>,
+    render: Rc<dyn Fn() -> String>,
+    seen: Option<u64>,
+}
+
+impl Binding {
+    pub fn new<T: 'static, F: 'static + Fn(&T) -> String>(signal: &Signal<T>, format: F) -> Self {
+        let version_signal = signal.clone();
+        let render_signal = signal.clone();
+        Self {
+            version: Rc::new(move || version_signal.version()),
+            render: Rc::new(move || render_signal.with(&format)),
+            seen: None,
+        }
+    }
+
+    /// Whether the signal changed since the text was last taken.
+    pub fn changed(&self) -> bool {
+        self.seen != Some((self.version)())
+    }
+
+    pub fn take(&mut self) -> String {
+        self.seen = Some((self.version)());
+        (self.render)()
+    }
+}
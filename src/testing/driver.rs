@@ -0,0 +1,239 @@
+use sdl2::{
+    event::Event,
+    keyboard::{Keycode, Mod, Scancode},
+    mouse::{MouseButton, MouseState, MouseWheelDirection},
+    pixels::Color,
+    VideoSubsystem,
+};
+
+use crate::{
+    app::record::Recording,
+    window::{builder::WindowBuilder, MyWindow, Renderer},
+    Action,
+};
+
+/// Frame time used by `step`, 60 fps like the default app loop.
+pub const FRAME_TIME: f32 = 1. / 60.;
+
+/// Feeds synthetic SDL events through `MyWindow::event` and steps frames deterministically.
+/// Actions returned by the widgets are collected for assertions instead of being applied.
+pub struct InputDriver {
+    window: MyWindow,
+    timestamp: u32,
+    mouse: (i32, i32),
+    mouse_state: u32,
+    actions: Vec<Action>,
+}
+
+impl InputDriver {
+    pub fn new(window: MyWindow) -> Self {
+        Self {
+            window,
+            timestamp: 0,
+            mouse: (0, 0),
+            mouse_state: 0,
+            actions: vec![],
+        }
+    }
+
+    /// Drives a hidden, software rendered window, works with the dummy video driver.
+    pub fn headless(video_subsystem: &VideoSubsystem, width: u32, height: u32) -> Result<Self, String> {
+        let window = WindowBuilder::new("Test", width, height)
+            .hidden()
+            .renderer(Renderer::Software)
+            .vsync(false)
+            .build(video_subsystem, |canvas, mut widgets| {
+                let mut c = canvas.borrow_mut();
+                c.set_draw_color(Color::RGB(0, 0, 0));
+                c.clear();
+
+                for widget in widgets.iter_mut() {
+                    widget.draw(&mut c);
+                }
+
+                c.present();
+            })?;
+        Ok(Self::new(window))
+    }
+
+    pub fn window(&self) -> &MyWindow {
+        &self.window
+    }
+
+    pub fn window_mut(&mut self) -> &mut MyWindow {
+        &mut self.window
+    }
+
+    /// Actions returned by widgets since the last call.
+    pub fn take_actions(&mut self) -> Vec<Action> {
+        std::mem::take(&mut self.actions)
+    }
+
+    /// Sends any event to the window, collecting the resulting actions.
+    pub fn send(&mut self, event: Event) -> &mut Self {
+        let mut actions = self.window.event(event);
+        self.actions.append(&mut actions);
+        self
+    }
+
+    /// Runs `frames` frames of `FRAME_TIME` each: ticks the widgets and redraws if needed.
+    pub fn step(&mut self, frames: u32) -> &mut Self {
+        for _ in 0..frames {
+            self.timestamp += (FRAME_TIME * 1000.) as u32;
+            self.window.tick(FRAME_TIME);
+            self.window.update();
+        }
+        self
+    }
+
+    /// Sends the events of a recording to this window, stepping frames between them so they
+    /// arrive at their recorded time. Ids of the recorded windows are ignored.
+    pub fn replay(&mut self, recording: &Recording) -> &mut Self {
+        for recorded in recording.events() {
+            while self.timestamp < recorded.time {
+                self.step(1);
+            }
+            self.send(retarget(recorded.event.clone(), self.window.get_id()));
+        }
+        self
+    }
+
+    pub fn mouse_move(&mut self, x: i32, y: i32) -> &mut Self {
+        let (old_x, old_y) = self.mouse;
+        self.mouse = (x, y);
+        let event = Event::MouseMotion {
+            timestamp: self.timestamp,
+            window_id: self.window.get_id(),
+            which: 0,
+            mousestate: MouseState::from_sdl_state(self.mouse_state),
+            x,
+            y,
+            xrel: x - old_x,
+            yrel: y - old_y,
+        };
+        self.send(event)
+    }
+
+    pub fn mouse_down(&mut self, button: MouseButton) -> &mut Self {
+        self.mouse_state |= button_mask(button);
+        let (x, y) = self.mouse;
+        let event = Event::MouseButtonDown {
+            timestamp: self.timestamp,
+            window_id: self.window.get_id(),
+            which: 0,
+            mouse_btn: button,
+            clicks: 1,
+            x,
+            y,
+        };
+        self.send(event)
+    }
+
+    pub fn mouse_up(&mut self, button: MouseButton) -> &mut Self {
+        self.mouse_state &= !button_mask(button);
+        let (x, y) = self.mouse;
+        let event = Event::MouseButtonUp {
+            timestamp: self.timestamp,
+            window_id: self.window.get_id(),
+            which: 0,
+            mouse_btn: button,
+            clicks: 1,
+            x,
+            y,
+        };
+        self.send(event)
+    }
+
+    /// Moves to the point and presses and releases the left button.
+    pub fn click(&mut self, x: i32, y: i32) -> &mut Self {
+        self.mouse_move(x, y).mouse_down(MouseButton::Left).mouse_up(MouseButton::Left)
+    }
+
+    /// Drags with the left button held in `steps` motion events.
+    pub fn drag(&mut self, from: (i32, i32), to: (i32, i32), steps: u32) -> &mut Self {
+        self.mouse_move(from.0, from.1).mouse_down(MouseButton::Left);
+        let steps = steps.max(1) as i32;
+        for i in 1..=steps {
+            let x = from.0 + (to.0 - from.0) * i / steps;
+            let y = from.1 + (to.1 - from.1) * i / steps;
+            self.mouse_move(x, y);
+        }
+        self.mouse_up(MouseButton::Left)
+    }
+
+    /// Scrolls at the current mouse position, positive `y` scrolls up.
+    pub fn wheel(&mut self, y: f32) -> &mut Self {
+        let event = Event::MouseWheel {
+            timestamp: self.timestamp,
+            window_id: self.window.get_id(),
+            which: 0,
+            x: 0,
+            y: y as i32,
+            direction: MouseWheelDirection::Normal,
+            precise_x: 0.,
+            precise_y: y,
+        };
+        self.send(event)
+    }
+
+    pub fn key_down(&mut self, keycode: Keycode, keymod: Mod) -> &mut Self {
+        let event = Event::KeyDown {
+            timestamp: self.timestamp,
+            window_id: self.window.get_id(),
+            keycode: Some(keycode),
+            scancode: Scancode::from_keycode(keycode),
+            keymod,
+            repeat: false,
+        };
+        self.send(event)
+    }
+
+    pub fn key_up(&mut self, keycode: Keycode, keymod: Mod) -> &mut Self {
+        let event = Event::KeyUp {
+            timestamp: self.timestamp,
+            window_id: self.window.get_id(),
+            keycode: Some(keycode),
+            scancode: Scancode::from_keycode(keycode),
+            keymod,
+            repeat: false,
+        };
+        self.send(event)
+    }
+
+    /// Presses and releases a key without modifiers.
+    pub fn key(&mut self, keycode: Keycode) -> &mut Self {
+        self.key_down(keycode, Mod::NOMOD).key_up(keycode, Mod::NOMOD)
+    }
+
+    pub fn text(&mut self, text: &str) -> &mut Self {
+        let event = Event::TextInput {
+            timestamp: self.timestamp,
+            window_id: self.window.get_id(),
+            text: text.to_string(),
+        };
+        self.send(event)
+    }
+}
+
+fn button_mask(button: MouseButton) -> u32 {
+    match button as u32 {
+        0 => 0,
+        b => 1 << (b - 1),
+    }
+}
+
+/// Moves a recorded event to the window of the driver, events without a window stay as they are.
+fn retarget(mut event: Event, id: u32) -> Event {
+    match &mut event {
+        Event::MouseMotion { window_id, .. }
+        | Event::MouseButtonDown { window_id, .. }
+        | Event::MouseButtonUp { window_id, .. }
+        | Event::MouseWheel { window_id, .. }
+        | Event::KeyDown { window_id, .. }
+        | Event::KeyUp { window_id, .. }
+        | Event::TextInput { window_id, .. }
+        | Event::Window { window_id, .. } => *window_id = id,
+        _ => {}
+    }
+    event
+}
@@ -0,0 +1,176 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use sdl2::{
+    pixels::{Color, PixelFormatEnum},
+    surface::Surface,
+};
+
+/// Set to "1" to (re)write golden images instead of comparing against them.
+pub const UPDATE_GOLDEN_ENV: &str = "UPDATE_GOLDEN";
+
+/// Result of comparing two images pixel by pixel.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImageDiff {
+    /// Pixels with at least one channel differing by more than the tolerance.
+    pub differing_pixels: usize,
+    /// Largest difference of a single channel over all pixels.
+    pub max_channel_delta: u8,
+}
+
+impl ImageDiff {
+    pub fn is_match(&self) -> bool {
+        self.differing_pixels == 0
+    }
+}
+
+/// Compares two images of the same size, channels within `tolerance` count as equal.
+pub fn compare(actual: &Surface, expected: &Surface, tolerance: u8) -> Result<ImageDiff, String> {
+    if actual.size() != expected.size() {
+        return Err(format!(
+            "image size {:?} does not match the expected {:?}",
+            actual.size(),
+            expected.size()
+        ));
+    }
+    let format = PixelFormatEnum::ARGB8888;
+    let actual = actual.convert_format(format)?;
+    let expected = expected.convert_format(format)?;
+    let (width, height) = actual.size();
+    let row = width as usize * format.byte_size_per_pixel();
+    let (actual_pitch, expected_pitch) = (actual.pitch() as usize, expected.pitch() as usize);
+
+    let mut diff = ImageDiff {
+        differing_pixels: 0,
+        max_channel_delta: 0,
+    };
+    actual.with_lock(|a| {
+        expected.with_lock(|e| {
+            for y in 0..height as usize {
+                let a_row = &a[y * actual_pitch..y * actual_pitch + row];
+                let e_row = &e[y * expected_pitch..y * expected_pitch + row];
+                for (a_px, e_px) in a_row.chunks(4).zip(e_row.chunks(4)) {
+                    let delta = a_px.iter().zip(e_px).map(|(a, e)| a.abs_diff(*e)).max().unwrap_or(0);
+                    diff.max_channel_delta = diff.max_channel_delta.max(delta);
+                    if delta > tolerance {
+                        diff.differing_pixels += 1;
+                    }
+                }
+            }
+        })
+    });
+    Ok(diff)
+}
+
+/// The colors of all pixels, row by row, for checks on single pixels.
+pub fn pixels(surface: &Surface) -> Result<Vec<Color>, String> {
+    let format = PixelFormatEnum::ARGB8888;
+    let surface = surface.convert_format(format)?;
+    let (width, height) = surface.size();
+    let pitch = surface.pitch() as usize;
+    Ok(surface.with_lock(|data| {
+        (0..height as usize)
+            .flat_map(|y| data[y * pitch..].chunks(4).take(width as usize))
+            .map(|px| {
+                // ARGB8888 is a native endian u32
+                let value = u32::from_ne_bytes([px[0], px[1], px[2], px[3]]);
+                Color::RGBA((value >> 16) as u8, (value >> 8) as u8, value as u8, (value >> 24) as u8)
+            })
+            .collect()
+    }))
+}
+
+/// Compares `actual` with the BMP at `golden`. With `UPDATE_GOLDEN=1` the golden image is
+/// written from `actual` instead, otherwise a missing one is an error. On a mismatch the actual
+/// image is saved next to the golden one as `<name>.actual.bmp` for inspection.
+pub fn assert_golden<P: AsRef<Path>>(actual: &Surface, golden: P, tolerance: u8) -> Result<(), String> {
+    let update = matches!(env::var(UPDATE_GOLDEN_ENV).as_deref(), Ok("1") | Ok("true"));
+    check_golden(actual, golden.as_ref(), tolerance, update)
+}
+
+fn check_golden(actual: &Surface, golden: &Path, tolerance: u8, update: bool) -> Result<(), String> {
+    if update {
+        if let Some(dir) = golden.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        }
+        return actual.save_bmp(golden);
+    }
+    if !golden.exists() {
+        return Err(format!(
+            "golden image {} is missing, run the tests with {}=1 to create it",
+            golden.display(),
+            UPDATE_GOLDEN_ENV
+        ));
+    }
+
+    let expected = Surface::load_bmp(golden)?;
+    let diff = compare(actual, &expected, tolerance)?;
+    if diff.is_match() {
+        return Ok(());
+    }
+    let actual_path = actual_path(golden);
+    actual.save_bmp(&actual_path)?;
+    Err(format!(
+        "{} differs from {} in {} pixels (max channel delta {}, tolerance {})",
+        actual_path.display(),
+        golden.display(),
+        diff.differing_pixels,
+        diff.max_channel_delta,
+        tolerance
+    ))
+}
+
+fn actual_path(golden: &Path) -> PathBuf {
+    let stem = golden.file_stem().map(|s| s.to_string_lossy()).unwrap_or_default();
+    golden.with_file_name(format!("{}.actual.bmp", stem))
+}
+
+#[cfg(test)]
+mod tests {
+    use sdl2::rect::Rect;
+
+    use super::*;
+
+    fn image(color: Color) -> Surface<'static> {
+        let mut surface = Surface::new(4, 3, PixelFormatEnum::RGB888).unwrap();
+        surface.fill_rect(None, color).unwrap();
+        surface
+    }
+
+    #[test]
+    fn compares_within_tolerance() {
+        let expected = image(Color::RGB(100, 100, 100));
+        let mut actual = image(Color::RGB(100, 100, 100));
+        actual.fill_rect(Rect::new(0, 0, 2, 1), Color::RGB(104, 100, 100)).unwrap();
+        actual.fill_rect(Rect::new(3, 2, 1, 1), Color::RGB(100, 90, 100)).unwrap();
+
+        let diff = compare(&actual, &expected, 4).unwrap();
+        assert_eq!(diff.differing_pixels, 1);
+        assert_eq!(diff.max_channel_delta, 10);
+        assert!(compare(&actual, &expected, 10).unwrap().is_match());
+        assert!(compare(&actual, &Surface::new(3, 3, PixelFormatEnum::RGB888).unwrap(), 0).is_err());
+        let pixels = pixels(&actual).unwrap();
+        assert_eq!(pixels.len(), 12);
+        assert_eq!(pixels[0], Color::RGB(104, 100, 100));
+        assert_eq!(pixels[11], Color::RGB(100, 90, 100));
+    }
+
+    #[test]
+    fn golden_files() {
+        let dir = env::temp_dir().join(format!("sdl_multiwindow_golden_{}", std::process::id()));
+        let golden = dir.join("image.bmp");
+        let gray = image(Color::RGB(100, 100, 100));
+
+        let missing = check_golden(&gray, &golden, 0, false).unwrap_err();
+        assert!(missing.contains("missing"), "{}", missing);
+        check_golden(&gray, &golden, 0, true).unwrap();
+        // matches whether or not the goldens are being updated
+        assert_golden(&gray, &golden, 0).unwrap();
+
+        check_golden(&image(Color::RGB(0, 0, 0)), &golden, 0, false).unwrap_err();
+        assert!(dir.join("image.actual.bmp").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
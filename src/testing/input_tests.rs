@@ -0,0 +1,160 @@
+use sdl2::keyboard::{Keycode, Mod};
+
+use crate::{
+    app::record::Recording,
+    utils::style::Style,
+    widgets::{button::Button, flex::Row, list::List, scrollview::ScrollView, Widget},
+    Action,
+};
+
+use super::{driver::InputDriver, stub::Stub, Headless};
+
+/// Adds focusable 40x40 stubs next to each other, 50 pixels apart.
+fn add_stubs(driver: &mut InputDriver, ids: &[&str]) {
+    for (i, id) in ids.iter().enumerate() {
+        let stub = Stub::new(40, 40).at(50 * i as i32, 0).id(id).focusable();
+        driver.window_mut().add_widget(Box::new(stub));
+    }
+    driver.step(1);
+}
+
+fn focused(driver: &InputDriver, id: &str) -> bool {
+    driver.window().find::<Stub>(id).unwrap().is_focused()
+}
+
+fn hovered(driver: &InputDriver, id: &str) -> bool {
+    driver.window().find::<Stub>(id).unwrap().is_hovered()
+}
+
+#[test]
+fn click_button() {
+    let sdl = Headless::init();
+    let mut driver = InputDriver::headless(&sdl.video, 200, 100).unwrap();
+    driver
+        .window_mut()
+        .add_widget(Box::new(Button::new(10, 10, 100, 30, "Quit", || Action::Quit, Style::new())));
+    driver.step(1);
+
+    // outside of the button nothing happens
+    driver.click(150, 80);
+    assert!(driver.take_actions().iter().all(|a| matches!(a, Action::None)));
+
+    driver.click(50, 20);
+    let actions = driver.take_actions();
+    assert_eq!(actions.iter().filter(|a| matches!(a, Action::Quit)).count(), 1);
+}
+
+#[test]
+fn replay_recorded_click() {
+    let sdl = Headless::init();
+    let mut driver = InputDriver::headless(&sdl.video, 200, 100).unwrap();
+    driver
+        .window_mut()
+        .add_widget(Box::new(Button::new(10, 10, 100, 30, "Quit", || Action::Quit, Style::new())));
+    driver.step(1);
+
+    // recorded in another window, the driver sends the events to its own
+    let recording = Recording::parse("0\t7\tmotion\t50\t20\t50\t20\t0\n40\t7\tdown\t1\t1\t50\t20\n80\t7\tup\t1\t1\t50\t20\n").unwrap();
+    assert_eq!(recording.duration(), 80);
+    driver.replay(&recording);
+    let actions = driver.take_actions();
+    assert_eq!(actions.iter().filter(|a| matches!(a, Action::Quit)).count(), 1);
+}
+
+#[test]
+fn drag_scrollview_thumb() {
+    let sdl = Headless::init();
+    let mut driver = InputDriver::headless(&sdl.video, 100, 100).unwrap();
+    let mut list = List::new(0, 0, 100, 100);
+    for i in 0..40 {
+        list = list.add_text(&format!("Item {}", i));
+    }
+    driver
+        .window_mut()
+        .add_widget(Box::new(ScrollView::new(Box::new(list), 0, 0, 100, 100).id("scroll")));
+    // the thumb gets its place when the scroll view is drawn
+    driver.step(1);
+
+    let content_height = {
+        let scroll = driver.window().find::<ScrollView>("scroll").unwrap();
+        scroll.child(0).unwrap().get_rect().height()
+    };
+    assert!(content_height > 100, "the list has to overflow the scroll view");
+
+    // the thumb starts at the top of the scrollbar on the right edge
+    driver.drag((95, 2), (95, 22), 4).step(1);
+
+    let scroll = driver.window().find::<ScrollView>("scroll").unwrap().scroll();
+    let expected = 20. * content_height as f32 / 100.;
+    assert!((scroll - expected).abs() < 1., "scrolled to {}, expected {}", scroll, expected);
+}
+
+#[test]
+fn focus_follows_reordered_siblings() {
+    let sdl = Headless::init();
+    let mut driver = InputDriver::headless(&sdl.video, 200, 100).unwrap();
+    add_stubs(&mut driver, &["a", "b", "c", "d"]);
+    driver.click(120, 20);
+    assert!(focused(&driver, "c"));
+
+    // removing a sibling before "c" moves it to index 1, Tab goes on to "d"
+    driver.window_mut().remove_widget(0).unwrap();
+    driver.key(Keycode::Tab);
+    assert!(focused(&driver, "d") && !focused(&driver, "c"));
+
+    // an inserted sibling moves "d" back to index 3, Shift+Tab returns to "c"
+    driver.window_mut().insert_widget(0, Box::new(Stub::new(40, 40).at(0, 50).id("e").focusable()));
+    driver.key_down(Keycode::Tab, Mod::LSHIFTMOD).key_up(Keycode::Tab, Mod::LSHIFTMOD);
+    assert!(focused(&driver, "c") && !focused(&driver, "d"));
+
+    // moved to the front "c" is followed by "e" in tree order
+    driver.window_mut().move_widget(2, 0).unwrap();
+    driver.key(Keycode::Tab);
+    assert!(focused(&driver, "e") && !focused(&driver, "c"));
+}
+
+#[test]
+fn focus_follows_reordered_children() {
+    let sdl = Headless::init();
+    let mut driver = InputDriver::headless(&sdl.video, 200, 100).unwrap();
+    let mut row = Row::new().id("row").rect(0, 0, 200, 40);
+    for id in ["p", "q", "r"] {
+        row = row.add_widget(Box::new(Stub::new(40, 40).id(id).focusable()));
+    }
+    driver.window_mut().add_widget(Box::new(row));
+    driver.step(1);
+    // the row places its children at 0, 40 and 80
+    driver.click(100, 20);
+    assert!(focused(&driver, "r"));
+
+    driver.window_mut().insert_child("row", 0, Box::new(Stub::new(40, 40).id("s").focusable())).unwrap();
+    driver.key_down(Keycode::Tab, Mod::LSHIFTMOD).key_up(Keycode::Tab, Mod::LSHIFTMOD);
+    assert!(focused(&driver, "q") && !focused(&driver, "r"));
+
+    driver.window_mut().move_child("row", 2, 0).unwrap();
+    driver.key(Keycode::Tab);
+    assert!(focused(&driver, "s") && !focused(&driver, "q"));
+}
+
+#[test]
+fn release_removed_widgets() {
+    let sdl = Headless::init();
+    let mut driver = InputDriver::headless(&sdl.video, 200, 100).unwrap();
+    add_stubs(&mut driver, &["a", "b", "c", "d"]);
+
+    // the hover of "c" is still tracked after a sibling before it is removed
+    driver.mouse_move(120, 20);
+    driver.window_mut().remove_widget(0).unwrap();
+    assert!(hovered(&driver, "c"));
+    driver.mouse_move(120, 80);
+    assert!(!hovered(&driver, "c"));
+
+    // a removed widget loses hover and focus, Tab starts over at the first widget
+    driver.click(70, 20);
+    assert!(focused(&driver, "b") && hovered(&driver, "b"));
+    let removed = driver.window_mut().remove_widget_by_id("b").unwrap();
+    let removed = removed.as_ref().as_any().downcast_ref::<Stub>().unwrap();
+    assert!(!removed.is_focused() && !removed.is_hovered());
+    driver.key(Keycode::Tab);
+    assert!(focused(&driver, "c") && !focused(&driver, "d"));
+}
@@ -0,0 +1,89 @@
+use std::{
+    env,
+    sync::{Mutex, MutexGuard},
+};
+
+use sdl2::{pixels::Color, render::Canvas, surface::Surface, video::Window, Sdl, VideoSubsystem};
+
+use crate::{
+    widgets::Widget,
+    window::{builder::WindowBuilder, init_sdl, Renderer, HEADLESS_ENV},
+};
+
+pub mod driver;
+pub mod golden;
+pub mod stub;
+
+mod input_tests;
+mod render_tests;
+
+/// SDL can only be used from one thread at a time, tests take turns through this lock.
+static SDL_LOCK: Mutex<()> = Mutex::new(());
+
+/// SDL on the dummy video driver for the duration of a test. Windows created from `video`
+/// must be dropped before it, which local variables declared later are.
+pub struct Headless {
+    pub video: VideoSubsystem,
+    _sdl: Sdl,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl Headless {
+    pub fn init() -> Self {
+        // a failed test poisons the lock, SDL itself is fine once its context is dropped
+        let lock = SDL_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        env::set_var(HEADLESS_ENV, "1");
+        let sdl = init_sdl().expect("SDL with the dummy video driver");
+        let video = sdl.video().expect("video subsystem");
+        Self {
+            video,
+            _sdl: sdl,
+            _lock: lock,
+        }
+    }
+}
+
+/// Renders `widgets` on a black background in a hidden, software rendered window and returns
+/// the frame. Together with the dummy video driver (`SDL_MULTIWINDOW_HEADLESS=1`) this needs no
+/// display or GPU.
+pub fn render_widgets(
+    video_subsystem: &VideoSubsystem,
+    width: u32,
+    height: u32,
+    widgets: Vec<Box<dyn Widget>>,
+) -> Result<Surface<'static>, String> {
+    render_canvas(video_subsystem, width, height, |_| {}, widgets)
+}
+
+/// Like `render_widgets`, with `draw` painting on the canvas before the widgets, e.g. shapes
+/// that are not widgets themselves.
+pub fn render_canvas<F: 'static + FnMut(&mut Canvas<Window>)>(
+    video_subsystem: &VideoSubsystem,
+    width: u32,
+    height: u32,
+    mut draw: F,
+    widgets: Vec<Box<dyn Widget>>,
+) -> Result<Surface<'static>, String> {
+    let mut window = WindowBuilder::new("Offscreen", width, height)
+        .hidden()
+        .renderer(Renderer::Software)
+        .vsync(false)
+        .build(video_subsystem, move |canvas, mut widgets| {
+            let mut c = canvas.borrow_mut();
+            c.set_draw_color(Color::RGB(0, 0, 0));
+            c.clear();
+
+            c.set_blend_mode(sdl2::render::BlendMode::Blend);
+
+            draw(&mut c);
+            for widget in widgets.iter_mut() {
+                widget.draw(&mut c);
+            }
+
+            c.present();
+        })?;
+    for widget in widgets {
+        window.add_widget(widget);
+    }
+    window.snapshot()
+}
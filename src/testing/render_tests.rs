@@ -0,0 +1,160 @@
+use sdl2::{pixels::Color, rect::Rect, surface::Surface};
+
+use crate::{
+    shapes::rounded_rect::RoundedRect,
+    utils::style::{FontStyle, Style, TextAlign},
+    widgets::{button::Button, list::List, scrollview::ScrollView, text::Text, Widget},
+    Action,
+};
+
+use super::{golden::pixels, render_canvas, render_widgets, Headless};
+
+/// The rendered frame with its pixels at hand, checked at points whose color does not depend on
+/// font rasterization or antialiasing.
+struct Frame {
+    width: u32,
+    pixels: Vec<Color>,
+}
+
+impl Frame {
+    fn new(surface: Surface) -> Self {
+        Self {
+            width: surface.width(),
+            pixels: pixels(&surface).unwrap(),
+        }
+    }
+
+    fn at(&self, x: u32, y: u32) -> Color {
+        let Color { r, g, b, .. } = self.pixels[(y * self.width + x) as usize];
+        Color::RGB(r, g, b)
+    }
+
+    /// Leftmost and rightmost column with a non black pixel in the rows `top..bottom`.
+    fn ink(&self, top: u32, bottom: u32) -> Option<(u32, u32)> {
+        let columns: Vec<u32> = (0..self.width)
+            .filter(|&x| (top..bottom).any(|y| self.at(x, y) != Color::RGB(0, 0, 0)))
+            .collect();
+        Some((*columns.first()?, *columns.last()?))
+    }
+}
+
+fn render(widgets: Vec<Box<dyn Widget>>, width: u32, height: u32) -> Frame {
+    let sdl = Headless::init();
+    Frame::new(render_widgets(&sdl.video, width, height, widgets).unwrap())
+}
+
+#[test]
+fn button_styles() {
+    let blue = Color::RGB(0x30, 0x60, 0xC0);
+    let plain = Button::new(10, 10, 140, 30, "Plain", || Action::None, Style::new());
+    let rounded = Button::new(
+        10,
+        50,
+        140,
+        30,
+        "Rounded",
+        || Action::None,
+        Style::new()
+            .background_color(blue)
+            .text_color(Color::WHITE)
+            .border_radius(10)
+            .border_width(0)
+            .font_style(FontStyle::Bold),
+    );
+    let frame = render(vec![Box::new(plain), Box::new(rounded)], 160, 90);
+
+    // the default style is white, the labels are short enough to leave the right end empty
+    assert_eq!(frame.at(140, 25), Color::WHITE);
+    assert_eq!(frame.at(140, 65), blue);
+    // the rounded corners leave the background visible
+    assert_eq!(frame.at(10, 50), Color::RGB(0, 0, 0));
+    assert_eq!(frame.at(149, 79), Color::RGB(0, 0, 0));
+    assert_eq!(frame.at(20, 65), blue);
+}
+
+#[test]
+fn rounded_rect() {
+    let green = Color::RGB(0x30, 0xA0, 0x60);
+    let sdl = Headless::init();
+    let surface = render_canvas(
+        &sdl.video,
+        120,
+        80,
+        move |canvas| RoundedRect::new(10, 10, 100, 60, 15).draw(canvas, green),
+        vec![],
+    )
+    .unwrap();
+    let frame = Frame::new(surface);
+
+    assert_eq!(frame.at(60, 40), green);
+    // straight edges are filled up to the rect
+    assert_eq!(frame.at(60, 11), green);
+    assert_eq!(frame.at(11, 40), green);
+    // corners are cut off
+    for (x, y) in [(11, 11), (108, 11), (11, 68), (108, 68)] {
+        assert_eq!(frame.at(x, y), Color::RGB(0, 0, 0), "corner at {}, {}", x, y);
+    }
+    assert_eq!(frame.at(5, 40), Color::RGB(0, 0, 0));
+}
+
+#[test]
+fn text_alignment() {
+    // all three texts are anchored at x = 100, the alignment says which part of them lies there
+    let texts: Vec<Box<dyn Widget>> = [TextAlign::Left, TextAlign::Center, TextAlign::Right]
+        .into_iter()
+        .enumerate()
+        .map(|(i, align)| {
+            let style = Style::new().text_color(Color::WHITE).text_align(align);
+            Box::new(Text::new(100, 30 * i as i32, "Aligned", style)) as Box<dyn Widget>
+        })
+        .collect();
+    let frame = render(texts, 200, 90);
+    let near = |a: u32, b: u32| a.abs_diff(b) <= 4;
+
+    let (left, right) = frame.ink(0, 30).unwrap();
+    assert!(near(left, 100) && right > 120, "left aligned ink at {}..{}", left, right);
+    let (left, right) = frame.ink(30, 60).unwrap();
+    assert!(near((left + right) / 2, 100) && left < 90, "centered ink at {}..{}", left, right);
+    let (left, right) = frame.ink(60, 90).unwrap();
+    assert!(near(right, 100) && left < 80, "right aligned ink at {}..{}", left, right);
+}
+
+#[test]
+fn scrollview_scrollbar() {
+    let mut list = List::new(0, 0, 150, 100);
+    for i in 0..20 {
+        list = list.add_text(&format!("Item {}", i));
+    }
+    let scroll = ScrollView::new(Box::new(list), 10, 10, 150, 100);
+    let frame = render(vec![Box::new(scroll)], 170, 120);
+
+    // the thumb sits at the top of the scrollbar on the right edge, the track below it
+    assert_eq!(frame.at(156, 12), Color::RGB(0x80, 0x80, 0x80));
+    assert_eq!(frame.at(156, 105), Color::RGB(0x60, 0x60, 0x60));
+    // left of the scrollbar is the content
+    assert_ne!(frame.at(148, 105), Color::RGB(0x60, 0x60, 0x60));
+}
+
+#[test]
+fn text_in_rect() {
+    // a container gives each text the full width, the alignment says where in it the text goes
+    let texts: Vec<Box<dyn Widget>> = [TextAlign::Left, TextAlign::Center, TextAlign::Right]
+        .into_iter()
+        .enumerate()
+        .map(|(i, align)| {
+            let style = Style::new().text_color(Color::WHITE).text_align(align);
+            let mut text = Text::new(0, 0, "Placed", style);
+            text.set_rect(Rect::new(10, 30 * i as i32, 180, 30));
+            Box::new(text) as Box<dyn Widget>
+        })
+        .collect();
+    let frame = render(texts, 200, 90);
+    let near = |a: u32, b: u32| a.abs_diff(b) <= 4;
+
+    let (left, right) = frame.ink(0, 30).unwrap();
+    assert!(near(left, 10), "left aligned ink at {}..{}", left, right);
+    let (left, right) = frame.ink(30, 60).unwrap();
+    assert!(near((left + right) / 2, 100), "centered ink at {}..{}", left, right);
+    let (left, right) = frame.ink(60, 90).unwrap();
+    assert!(near(right, 190), "right aligned ink at {}..{}", left, right);
+}
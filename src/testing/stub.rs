@@ -0,0 +1,93 @@
+use std::cell::RefMut;
+
+use sdl2::{rect::Rect, render::Canvas, video::Window};
+
+use crate::widgets::Widget;
+
+/// A widget that draws nothing and asks for a fixed size, for layout tests without SDL.
+/// It remembers whether it is hovered or focused for the tests of the event routing.
+#[derive(Clone)]
+pub struct Stub {
+    rect: Rect,
+    hint: (u32, u32),
+    id: Option<String>,
+    focusable: bool,
+    hovered: bool,
+    focused: bool,
+}
+
+impl Stub {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            rect: Rect::new(0, 0, width, height),
+            hint: (width, height),
+            id: None,
+            focusable: false,
+            hovered: false,
+            focused: false,
+        }
+    }
+
+    pub fn boxed(width: u32, height: u32) -> Box<dyn Widget> {
+        Box::new(Self::new(width, height))
+    }
+
+    pub fn at(mut self, x: i32, y: i32) -> Self {
+        self.rect.reposition((x, y));
+        self
+    }
+
+    pub fn id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    pub fn focusable(mut self) -> Self {
+        self.focusable = true;
+        self
+    }
+
+    pub fn is_hovered(&self) -> bool {
+        self.hovered
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+}
+
+impl Widget for Stub {
+    fn draw(&mut self, _canvas: &mut RefMut<Canvas<Window>>) {}
+
+    fn on_hover(&mut self, hover: bool) {
+        self.hovered = hover;
+    }
+
+    fn focusable(&self) -> bool {
+        self.focusable
+    }
+
+    fn on_focus(&mut self) {
+        self.focused = true;
+    }
+
+    fn on_blur(&mut self) {
+        self.focused = false;
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    fn get_rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn size_hint(&self) -> (u32, u32) {
+        self.hint
+    }
+}
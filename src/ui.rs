@@ -0,0 +1,145 @@
+/// Builds a widget tree from a nested description and expands to the usual constructors and
+/// builder calls.
+///
+/// ```ignore
+/// let tree = ui! {
+///     Column(spacing = 10).rect(10, 10, 200, 80) {
+///         Button("Ok", on_click = || Action::Quit, style = .text_color(Color::RED)).id("ok"),
+///         ScrollView(rect = (0, 0, 200, 400)) {
+///             List {
+///                 Text("first"),
+///                 Widget(my_text),
+///             }
+///         },
+///     }
+/// };
+/// ```
+///
+/// A widget is its type followed by arguments in parentheses, any number of builder calls like
+/// `.id("ok")` and, for containers, its children in braces:
+///
+/// - `Button(label, on_click = f)` with the optional arguments `rect = (x, y, width, height)`
+///   and `style`.
+/// - `Text(text)` with the optional arguments `at = (x, y)` and `style`.
+/// - `List` and `ScrollView` with the optional argument `rect = (x, y, width, height)`,
+///   a `ScrollView` has exactly one child.
+/// - `Widget(expr)` inserts a widget built elsewhere, e.g. a `Grid` or `AnchorLayout`, whose
+///   children need more than `add_widget` and which are not supported directly.
+/// - Other containers, like `Column`, `Row` and `Stack`, are created with `new()`, their
+///   arguments are builder calls, `spacing = 10` being `.spacing(10)`, and the children are
+///   added with `add_widget`.
+/// - Other widgets without children are created with `new(args)`.
+///
+/// A `style` is either an expression or a chain of `Style` methods starting with a dot, like
+/// `style = .font_size(20).text_color(Color::RED)`.
+macro_rules! ui {
+    // children of a container, one `add_widget` per child
+    (@add $parent:expr ;) => {
+        $parent
+    };
+    (@add $parent:expr ; $name:ident $(($($args:tt)*))? $(. $method:ident ($($margs:tt)*))* $({ $($children:tt)* })? $(, $($rest:tt)*)?) => {
+        ui!(@add $parent.add_widget(::std::boxed::Box::new(
+            ui!($name $(($($args)*))? $(. $method($($margs)*))* $({ $($children)* })?)
+        )) ; $($($rest)*)?)
+    };
+    (@add $parent:expr ; $($other:tt)*) => {
+        compile_error!(concat!("expected a widget like `Text(\"..\")` or `Column { .. }`, found `", stringify!($($other)*), "`"))
+    };
+
+    // named arguments of the widgets with their own rules, stored into the given variables
+    (@args $widget:ident $vars:tt ;) => {};
+    (@args $widget:ident [$rect:ident $at:ident $style:ident] ; style = $(. $method:ident ($($margs:tt)*))+ $(, $($rest:tt)*)?) => {
+        ui!(@arg $widget [$rect $at $style] style $crate::utils::style::Style::new() $(. $method($($margs)*))+);
+        ui!(@args $widget [$rect $at $style] ; $($($rest)*)?);
+    };
+    (@args $widget:ident $vars:tt ; $key:ident = $value:expr $(, $($rest:tt)*)?) => {
+        ui!(@arg $widget $vars $key $value);
+        ui!(@args $widget $vars ; $($($rest)*)?);
+    };
+    (@args $widget:ident $vars:tt ; $($other:tt)*) => {
+        compile_error!(concat!("expected `name = value` arguments for `", stringify!($widget), "`, found `", stringify!($($other)*), "`"))
+    };
+    (@arg Text [$rect:ident $at:ident $style:ident] at $value:expr) => {
+        $at = Some($value);
+    };
+    (@arg Text [$rect:ident $at:ident $style:ident] style $value:expr) => {
+        $style = Some($value);
+    };
+    (@arg Text $vars:tt $key:ident $value:expr) => {
+        compile_error!(concat!("unknown `Text` argument `", stringify!($key), "`, expected `at` or `style`"))
+    };
+    (@arg Button [$rect:ident $at:ident $style:ident] style $value:expr) => {
+        $style = Some($value);
+    };
+    (@arg $widget:ident [$rect:ident $at:ident $style:ident] rect $value:expr) => {
+        $rect = Some($value);
+    };
+    (@arg Button $vars:tt $key:ident $value:expr) => {
+        compile_error!(concat!("unknown `Button` argument `", stringify!($key), "`, expected `rect` or `style`"))
+    };
+    (@arg $widget:ident $vars:tt $key:ident $value:expr) => {
+        compile_error!(concat!("unknown `", stringify!($widget), "` argument `", stringify!($key), "`, expected `rect`"))
+    };
+
+    (Button($label:expr, on_click = $on_click:expr $(, $($args:tt)*)?) $(. $method:ident ($($margs:tt)*))*) => {{
+        #[allow(unused_mut, unused_assignments)]
+        let mut rect: Option<(i32, i32, u32, u32)> = None;
+        #[allow(unused_mut, unused_assignments)]
+        let mut style: Option<$crate::utils::style::Style> = None;
+        ui!(@args Button [rect rect style] ; $($($args)*)?);
+        let (x, y, width, height) = rect.unwrap_or((0, 0, 100, 20));
+        let style = style.unwrap_or_else($crate::utils::style::Style::new);
+        $crate::widgets::button::Button::new(x, y, width, height, $label, $on_click, style) $(. $method($($margs)*))*
+    }};
+    (Button $($other:tt)*) => {
+        compile_error!("`Button` takes a label and a click handler first, e.g. `Button(\"Ok\", on_click = || Action::None)`")
+    };
+    (Text($text:expr $(, $($args:tt)*)?) $(. $method:ident ($($margs:tt)*))*) => {{
+        #[allow(unused_mut, unused_assignments)]
+        let mut at: Option<(i32, i32)> = None;
+        #[allow(unused_mut, unused_assignments)]
+        let mut style: Option<$crate::utils::style::Style> = None;
+        ui!(@args Text [at at style] ; $($($args)*)?);
+        let (x, y) = at.unwrap_or((0, 0));
+        let style = style.unwrap_or_else($crate::utils::style::Style::new);
+        $crate::widgets::text::Text::new(x, y, $text, style) $(. $method($($margs)*))*
+    }};
+    (List $(($($args:tt)*))? $(. $method:ident ($($margs:tt)*))* { $($children:tt)* }) => {{
+        #[allow(unused_mut, unused_assignments)]
+        let mut rect: Option<(i32, i32, u32, u32)> = None;
+        ui!(@args List [rect rect rect] ; $($($args)*)?);
+        let (x, y, width, height) = rect.unwrap_or((0, 0, 100, 100));
+        ui!(@add $crate::widgets::list::List::new(x, y, width, height) $(. $method($($margs)*))* ; $($children)*)
+    }};
+    (ScrollView $(($($args:tt)*))? $(. $method:ident ($($margs:tt)*))* {
+        $name:ident $(($($cargs:tt)*))? $(. $cmethod:ident ($($cmargs:tt)*))* $({ $($children:tt)* })? $(,)?
+    }) => {{
+        #[allow(unused_mut, unused_assignments)]
+        let mut rect: Option<(i32, i32, u32, u32)> = None;
+        ui!(@args ScrollView [rect rect rect] ; $($($args)*)?);
+        let (x, y, width, height) = rect.unwrap_or((0, 0, 100, 100));
+        let child = ::std::boxed::Box::new(ui!($name $(($($cargs)*))? $(. $cmethod($($cmargs)*))* $({ $($children)* })?));
+        $crate::widgets::scrollview::ScrollView::new(child, x, y, width, height) $(. $method($($margs)*))*
+    }};
+    (ScrollView $($other:tt)*) => {
+        compile_error!("`ScrollView` takes exactly one child, e.g. `ScrollView { List { .. } }`")
+    };
+    (Widget($widget:expr) $(. $method:ident ($($margs:tt)*))*) => {
+        $widget $(. $method($($margs)*))*
+    };
+    (Grid $($other:tt)*) => {
+        compile_error!("`Grid` is not supported, its children need a `GridCell`, build it in code and insert it with `Widget(..)`")
+    };
+    (AnchorLayout $($other:tt)*) => {
+        compile_error!("`AnchorLayout` is not supported, its children need a name and constraints, build it in code and insert it with `Widget(..)`")
+    };
+    ($container:ident $(($($key:ident = $value:expr),* $(,)?))? $(. $method:ident ($($margs:tt)*))* { $($children:tt)* }) => {
+        ui!(@add $container::new() $($(. $key($value))*)? $(. $method($($margs)*))* ; $($children)*)
+    };
+    ($widget:ident $(($($args:expr),* $(,)?))? $(. $method:ident ($($margs:tt)*))*) => {
+        $widget::new($($($args),*)?) $(. $method($($margs)*))*
+    };
+    ($($other:tt)*) => {
+        compile_error!(concat!("expected a widget like `Text(\"..\")` or `Column { .. }`, found `", stringify!($($other)*), "`"))
+    };
+}
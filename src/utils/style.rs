@@ -13,11 +13,14 @@ pub enum TextAlign {
     Right,
 }
 
+/// Which states a style value applies to. `Normal` and `Multiple` also set the focused state,
+/// so focused widgets look like unfocused ones unless `Focused` overrides it.
 pub enum Params<T> {
     All(T),
     Normal(T),
     Hover(T),
     Clicked(T),
+    Focused(T),
     Multiple(T, T, T),
 }
 
@@ -48,6 +51,7 @@ macro_rules! style_struct {
             pub normal: StyleValues,
             pub hover: StyleValues,
             pub clicked: StyleValues,
+            pub focused: StyleValues,
         }
 
         impl Style {
@@ -56,21 +60,24 @@ macro_rules! style_struct {
                     normal: StyleValues::default(),
                     hover: StyleValues::default(),
                     clicked: StyleValues::default(),
+                    focused: StyleValues::default(),
                 }
             }
 
             $(
                 pub fn $field<T: Into<Params<$type>>>(mut self, param: T) -> Self {
-                    let (normal, hover, clicked) = match param.into() {
-                        Params::All(value) => (value.clone(), value.clone(), value),
-                        Params::Normal(value) => (value.clone(), self.hover.$field.clone(), self.clicked.$field.clone()),
-                        Params::Hover(value) => (self.normal.$field.clone(), value.clone(), self.clicked.$field.clone()),
-                        Params::Clicked(value) => (self.normal.$field.clone(), self.hover.$field.clone(), value.clone()),
-                        Params::Multiple(normal, hover, clicked) => (normal, hover, clicked),
+                    let (normal, hover, clicked, focused) = match param.into() {
+                        Params::All(value) => (value.clone(), value.clone(), value.clone(), value),
+                        Params::Normal(value) => (value.clone(), self.hover.$field.clone(), self.clicked.$field.clone(), value),
+                        Params::Hover(value) => (self.normal.$field.clone(), value, self.clicked.$field.clone(), self.focused.$field.clone()),
+                        Params::Clicked(value) => (self.normal.$field.clone(), self.hover.$field.clone(), value, self.focused.$field.clone()),
+                        Params::Focused(value) => (self.normal.$field.clone(), self.hover.$field.clone(), self.clicked.$field.clone(), value),
+                        Params::Multiple(normal, hover, clicked) => (normal.clone(), hover, clicked, normal),
                     };
                     self.normal.$field = normal;
                     self.hover.$field = hover;
                     self.clicked.$field = clicked;
+                    self.focused.$field = focused;
                     self
                 }
             )*
@@ -118,6 +125,7 @@ impl Style {
         self.normal = self.normal.adjust(rect);
         self.hover = self.hover.adjust(rect);
         self.clicked = self.clicked.adjust(rect);
+        self.focused = self.focused.adjust(rect);
         self
     }
 }
\ No newline at end of file
@@ -0,0 +1,392 @@
+use std::cell::RefMut;
+
+use sdl2::{
+    rect::{Point, Rect},
+    render::Canvas,
+    video::Window,
+};
+
+use super::{children::Children, Widget};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Edge {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    CenterX,
+    CenterY,
+}
+
+impl Edge {
+    fn of(self, rect: Rect) -> i32 {
+        match self {
+            Edge::Left => rect.left(),
+            Edge::Right => rect.right(),
+            Edge::Top => rect.top(),
+            Edge::Bottom => rect.bottom(),
+            Edge::CenterX => rect.x() + rect.width() as i32 / 2,
+            Edge::CenterY => rect.y() + rect.height() as i32 / 2,
+        }
+    }
+}
+
+/// An edge of the parent or of a named sibling, moved by an offset.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Anchor {
+    sibling: Option<String>,
+    edge: Edge,
+    offset: i32,
+}
+
+impl Anchor {
+    pub fn parent(edge: Edge) -> Self {
+        Self {
+            sibling: None,
+            edge,
+            offset: 0,
+        }
+    }
+
+    pub fn sibling(name: &str, edge: Edge) -> Self {
+        Self {
+            sibling: Some(name.to_string()),
+            edge,
+            offset: 0,
+        }
+    }
+
+    /// Moves the anchor right or down, negative offsets move it left or up.
+    pub fn offset(mut self, offset: i32) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+/// Where the edges of a child of an `AnchorLayout` go. An axis with both edges anchored
+/// stretches the child between them, otherwise the child keeps its width or height, taken from
+/// `width`/`height` or its size hint, and is placed by the one edge or center that is anchored.
+/// Children without anchors on an axis sit at the parent's left or top edge.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Constraints {
+    left: Option<Anchor>,
+    right: Option<Anchor>,
+    top: Option<Anchor>,
+    bottom: Option<Anchor>,
+    center_x: Option<Anchor>,
+    center_y: Option<Anchor>,
+    width: Option<u32>,
+    height: Option<u32>,
+}
+
+impl Constraints {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn left(mut self, anchor: Anchor) -> Self {
+        self.left = Some(anchor);
+        self
+    }
+
+    pub fn right(mut self, anchor: Anchor) -> Self {
+        self.right = Some(anchor);
+        self
+    }
+
+    pub fn top(mut self, anchor: Anchor) -> Self {
+        self.top = Some(anchor);
+        self
+    }
+
+    pub fn bottom(mut self, anchor: Anchor) -> Self {
+        self.bottom = Some(anchor);
+        self
+    }
+
+    pub fn center_x(mut self, anchor: Anchor) -> Self {
+        self.center_x = Some(anchor);
+        self
+    }
+
+    pub fn center_y(mut self, anchor: Anchor) -> Self {
+        self.center_y = Some(anchor);
+        self
+    }
+
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = Some(height);
+        self
+    }
+}
+
+#[derive(Clone)]
+struct Anchoring {
+    name: String,
+    constraints: Constraints,
+    /// Whether unresolvable anchors were reported already, they are only reported once.
+    reported: bool,
+}
+
+/// Positions children by anchoring their edges to the edges of the layout or of siblings,
+/// e.g. pinning a button to the bottom right corner or filling the space below a toolbar.
+/// The layout covers the bounds it is laid out in, for a root widget the whole window, and
+/// resolves the constraints again on every resize.
+#[derive(Clone)]
+pub struct AnchorLayout {
+    children: Children<Anchoring>,
+    rect: Rect,
+    id: Option<String>,
+    dirty: bool,
+}
+
+impl AnchorLayout {
+    pub fn new() -> Self {
+        Self {
+            children: Children::new(),
+            rect: Rect::new(0, 0, 0, 0),
+            id: None,
+            dirty: true,
+        }
+    }
+
+    pub fn id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    /// Adds a child that siblings can anchor to by `name`.
+    pub fn add_widget(mut self, name: &str, widget: Box<dyn Widget>, constraints: Constraints) -> Self {
+        let anchoring = Anchoring {
+            name: name.to_string(),
+            constraints,
+            reported: false,
+        };
+        self.children.push(widget, anchoring);
+        self
+    }
+
+    /// Resolves the children in dependency order. Anchors to unknown siblings, or to siblings
+    /// that depend on each other, fall back to the parent and are reported the first time.
+    fn arrange(&mut self) {
+        let hints = self.children.arranged();
+        let mut rects: Vec<Option<Rect>> = vec![None; self.children.len()];
+        loop {
+            let mut progress = false;
+            for i in 0..self.children.len() {
+                if rects[i].is_none() {
+                    rects[i] = self.resolve(i, &hints, &rects, false);
+                    progress |= rects[i].is_some();
+                }
+            }
+            if !progress {
+                break;
+            }
+        }
+        let unresolved: Vec<bool> = rects.iter().map(|r| r.is_none()).collect();
+        for i in 0..self.children.len() {
+            if unresolved[i] {
+                rects[i] = self.resolve(i, &hints, &rects, true);
+            }
+        }
+
+        for ((child, rect), unresolved) in self.children.iter_mut().zip(rects).zip(unresolved) {
+            if unresolved && !child.placement.reported {
+                eprintln!(
+                    "Anchors of \"{}\" refer to unknown or circular siblings, using the parent instead",
+                    child.placement.name
+                );
+                child.placement.reported = true;
+            }
+            if let Some(rect) = rect {
+                child.widget.set_rect(rect);
+                child.widget.layout(rect);
+            }
+        }
+        self.dirty = true;
+    }
+
+    /// Rect of child `i`, or `None` while a sibling it depends on has no rect yet.
+    /// With `fallback` unresolved siblings are replaced by the parent.
+    fn resolve(&self, i: usize, hints: &[(u32, u32)], rects: &[Option<Rect>], fallback: bool) -> Option<Rect> {
+        let constraints = &self.children[i].placement.constraints;
+        let mut unresolved = false;
+        let mut position = |anchor: &Option<Anchor>| {
+            let anchor = anchor.as_ref()?;
+            let target = match &anchor.sibling {
+                None => Some(self.rect),
+                Some(name) => self
+                    .children
+                    .iter()
+                    .position(|c| &c.placement.name == name)
+                    .and_then(|s| rects[s])
+                    .or(if fallback { Some(self.rect) } else { None }),
+            };
+            match target {
+                Some(rect) => Some(anchor.edge.of(rect) + anchor.offset),
+                None => {
+                    unresolved = true;
+                    None
+                }
+            }
+        };
+        let (left, right, center_x) = (
+            position(&constraints.left),
+            position(&constraints.right),
+            position(&constraints.center_x),
+        );
+        let (top, bottom, center_y) = (
+            position(&constraints.top),
+            position(&constraints.bottom),
+            position(&constraints.center_y),
+        );
+        if unresolved {
+            return None;
+        }
+
+        let (hint_width, hint_height) = hints[i];
+        let (x, width) = place(left, right, center_x, constraints.width.unwrap_or(hint_width), self.rect.left());
+        let (y, height) = place(top, bottom, center_y, constraints.height.unwrap_or(hint_height), self.rect.top());
+        Some(Rect::new(x, y, width, height))
+    }
+}
+
+/// Start and length on one axis from the anchored positions of its edges.
+fn place(start: Option<i32>, end: Option<i32>, center: Option<i32>, len: u32, origin: i32) -> (i32, u32) {
+    match (start, end, center) {
+        (Some(start), Some(end), _) => (start, (end - start).max(0) as u32),
+        (Some(start), None, _) => (start, len),
+        (None, Some(end), _) => (end - len as i32, len),
+        (None, None, Some(center)) => (center - len as i32 / 2, len),
+        (None, None, None) => (origin, len),
+    }
+}
+
+impl Default for AnchorLayout {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Widget for AnchorLayout {
+    fn draw(&mut self, canvas: &mut RefMut<Canvas<Window>>) {
+        if self.children.needs_arrange() {
+            self.arrange();
+        }
+        self.children.draw(canvas);
+        self.dirty = false;
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+        self.arrange();
+    }
+
+    fn get_rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// Children are placed relative to the rect it is given, it has no size of its own.
+    fn size_hint(&self) -> (u32, u32) {
+        (0, 0)
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn hit_test(&self, point: Point) -> bool {
+        self.children.hit_test(point)
+    }
+
+    fn layout(&mut self, bounds: Rect) {
+        self.rect = bounds;
+        self.arrange();
+    }
+
+    fn tick(&mut self, dt: f32) {
+        self.children.tick(dt);
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty || self.children.is_dirty()
+    }
+
+    fn child_count(&self) -> usize {
+        self.children.len()
+    }
+
+    fn child(&self, index: usize) -> Option<&dyn Widget> {
+        self.children.widget(index)
+    }
+
+    fn child_mut(&mut self, index: usize) -> Option<&mut (dyn Widget + 'static)> {
+        self.children.widget_mut(index)
+    }
+
+    /// The child is named after its id and starts without constraints, so it sits in the top
+    /// left corner until it is replaced or the layout is rebuilt.
+    fn insert_child(&mut self, index: usize, widget: Box<dyn Widget>) -> Result<(), String> {
+        let anchoring = Anchoring {
+            name: widget.get_id().unwrap_or_default().to_string(),
+            constraints: Constraints::default(),
+            reported: false,
+        };
+        self.children.insert(index, widget, anchoring);
+        Ok(())
+    }
+
+    fn remove_child(&mut self, index: usize) -> Option<Box<dyn Widget>> {
+        self.children.remove(index)
+    }
+
+    fn replace_child(&mut self, index: usize, widget: Box<dyn Widget>) -> Option<Box<dyn Widget>> {
+        self.children.replace(index, widget)
+    }
+
+    fn move_child(&mut self, from: usize, to: usize) -> Result<(), String> {
+        self.children.move_child(from, to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::stub::Stub;
+
+    fn rect(layout: &AnchorLayout, index: usize) -> Rect {
+        layout.child(index).unwrap().get_rect()
+    }
+
+    #[test]
+    fn pins_to_right_bottom_corner() {
+        let constraints = Constraints::new()
+            .right(Anchor::parent(Edge::Right).offset(-10))
+            .bottom(Anchor::parent(Edge::Bottom).offset(-10));
+        let mut layout = AnchorLayout::new().add_widget("ok", Stub::boxed(40, 20), constraints);
+        layout.set_rect(Rect::new(0, 0, 200, 100));
+        assert_eq!(rect(&layout, 0), Rect::new(150, 70, 40, 20));
+        // the corner is followed on resize
+        layout.set_rect(Rect::new(0, 0, 300, 150));
+        assert_eq!(rect(&layout, 0), Rect::new(250, 120, 40, 20));
+    }
+
+    #[test]
+    fn circular_siblings_fall_back_to_parent() {
+        let mut layout = AnchorLayout::new()
+            .add_widget("a", Stub::boxed(40, 20), Constraints::new().right(Anchor::sibling("b", Edge::Left)))
+            .add_widget("b", Stub::boxed(40, 20), Constraints::new().left(Anchor::sibling("a", Edge::Right)))
+            .add_widget("c", Stub::boxed(40, 20), Constraints::new().top(Anchor::sibling("missing", Edge::Bottom)));
+        layout.set_rect(Rect::new(0, 0, 200, 100));
+        // "a" takes the parent in place of "b", which then resolves against "a"
+        assert_eq!(rect(&layout, 0), Rect::new(160, 0, 40, 20));
+        assert_eq!(rect(&layout, 1), Rect::new(200, 0, 40, 20));
+        assert_eq!(rect(&layout, 2), Rect::new(0, 100, 40, 20));
+        assert!(layout.children.iter().all(|c| c.placement.reported));
+    }
+}
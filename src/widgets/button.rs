@@ -4,19 +4,25 @@ use std::{
 };
 
 use sdl2::{
-    rect::{Point, Rect},
+    event::Event,
+    keyboard::Keycode,
+    rect::Rect,
     render::Canvas,
     video::Window,
 };
 
-use crate::{window::MyWindow, Action, utils::style::{Style, StyleValues, TextAlign}, CustomCanvas};
+use crate::{window::{context::WindowCtx, dispatch::EventCtx}, Action, utils::style::{Style, StyleValues, TextAlign}, CustomCanvas};
 
 use super::{text::Text, Widget};
 
 #[derive(Clone)]
 pub struct Button<'a> {
     rect: Rect,
+    preferred: (u32, u32),
+    id: Option<String>,
     hover: bool,
+    focused: bool,
+    dirty: bool,
     label: Text<'a>,
     on_click: Rc<RefCell<Box<dyn Fn() -> Action>>>,
     style: Style,
@@ -33,36 +39,67 @@ impl<'a> Button<'a> {
         style: Style,
     ) -> Self {
         let rect = Rect::new(x, y, width, height);
-        let xy = match style.normal.text_align {
-            TextAlign::Center => {
-                (x + width as i32 / 2, y)
-            }
-            TextAlign::Left => (x, y),
-            TextAlign::Right => {
-                (x + width as i32, y)
-            }
-        };
+        let xy = Self::label_anchor(rect, &style.normal.text_align);
         Self {
             rect,
+            preferred: (width, height),
+            id: None,
             hover: false,
+            focused: false,
+            dirty: true,
             label: Text::new(xy.0, xy.1, text, style.clone()),
             on_click: Rc::new(RefCell::new(Box::new(on_click))),
             style: style.adjust(rect),
         }
     }
 
+    pub fn id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    fn label_anchor(rect: Rect, align: &TextAlign) -> (i32, i32) {
+        match align {
+            TextAlign::Center => {
+                (rect.x() + rect.width() as i32 / 2, rect.y())
+            }
+            TextAlign::Left => (rect.x(), rect.y()),
+            TextAlign::Right => {
+                (rect.x() + rect.width() as i32, rect.y())
+            }
+        }
+    }
+
     fn get_style(&self) -> &StyleValues {
         if self.hover {
             &self.style.hover
+        } else if self.focused {
+            &self.style.focused
         } else {
             &self.style.normal
         }
     }
 }
 
-impl<'a> Widget for Button<'a> {
+impl Widget for Button<'static> {
 
     fn draw(&mut self, canvas: &mut RefMut<Canvas<Window>>) {
+        if self.focused {
+            // focus ring, a border wide frame around the button
+            let width = self.style.focused.border_width.max(1);
+            let ring = Rect::new(
+                self.rect.x() - width as i32,
+                self.rect.y() - width as i32,
+                self.rect.width() + 2 * width,
+                self.rect.height() + 2 * width,
+            );
+            canvas.set_draw_color(self.style.focused.border_color);
+            if self.style.focused.border_radius != 0 {
+                canvas.rounded_rect(ring, self.style.focused.border_radius + width);
+            } else {
+                let _ = canvas.fill_rect(ring);
+            }
+        }
         canvas.set_draw_color(self.get_style().background_color);
         if self.get_style().border_radius != 0 {
             canvas
@@ -73,34 +110,73 @@ impl<'a> Widget for Button<'a> {
                 .expect("Could not draw rect");
         }
         self.label.draw(canvas);
+        self.dirty = false;
     }
 
-    fn event(&mut self, event: sdl2::event::Event, win: &MyWindow) -> Action {
+    fn event(&mut self, event: Event, ctx: &mut EventCtx) -> Action {
         match event {
-            sdl2::event::Event::MouseMotion {
-                window_id, x, y, ..
+            Event::MouseButtonDown { .. }
+            | Event::KeyDown {
+                keycode: Some(Keycode::Return | Keycode::KpEnter | Keycode::Space),
+                repeat: false,
+                ..
             } => {
-                if window_id == win.get_id() {
-                    self.hover = self.rect.contains_point(Point::new(x, y));
-                }
-            }
-            sdl2::event::Event::MouseButtonDown { window_id, .. } => {
-                if self.hover && window_id == win.get_id() {
-                    println!("{}", win.get_id());
-                    return (self.on_click.borrow())();
-                }
+                ctx.set_handled();
+                (self.on_click.borrow())()
             }
-            _ => {}
+            _ => Action::None,
+        }
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn on_focus(&mut self) {
+        self.focused = true;
+        self.dirty = true;
+    }
+
+    fn on_blur(&mut self) {
+        self.focused = false;
+        self.dirty = true;
+    }
+
+    fn on_hover(&mut self, hover: bool) {
+        if hover != self.hover {
+            self.hover = hover;
+            self.dirty = true;
         }
-        Action::None
     }
 
     fn set_rect(&mut self, rect: Rect) {
         self.rect = rect;
-        self.label.set_rect(rect);
+        let (x, y) = Self::label_anchor(rect, &self.style.normal.text_align);
+        self.label.set_anchor(x, y);
+        self.dirty = true;
+    }
+
+    fn on_mount(&mut self, ctx: &WindowCtx) {
+        self.label.on_mount(ctx);
+    }
+
+    fn on_unmount(&mut self) {
+        self.label.on_unmount();
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty || self.label.is_dirty()
     }
 
     fn get_rect(&self) -> Rect {
         self.rect
     }
+
+    fn size_hint(&self) -> (u32, u32) {
+        self.preferred
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
 }
@@ -0,0 +1,137 @@
+use std::{cell::RefMut, ops::Index};
+
+use sdl2::{rect::Point, render::Canvas, video::Window};
+
+use super::{move_item, Widget};
+
+/// A child of a container with what the container needs to place it.
+#[derive(Clone)]
+pub struct Child<P> {
+    pub widget: Box<dyn Widget>,
+    pub placement: P,
+}
+
+/// The children of a layout container, with the size hints they were last laid out at.
+/// Containers lay their children out again in `draw` when `needs_arrange` says so, which is
+/// after any change to the children or to one of their size hints. A child whose size hint
+/// changes is dirty itself, so `is_dirty` does not have to look at the hints.
+#[derive(Clone)]
+pub struct Children<P> {
+    items: Vec<Child<P>>,
+    hints: Vec<(u32, u32)>,
+    changed: bool,
+}
+
+impl<P> Children<P> {
+    pub fn new() -> Self {
+        Self {
+            items: vec![],
+            hints: vec![],
+            changed: true,
+        }
+    }
+
+    pub fn push(&mut self, widget: Box<dyn Widget>, placement: P) {
+        self.items.push(Child { widget, placement });
+        self.changed = true;
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Child<P>> {
+        self.items.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, Child<P>> {
+        self.items.iter_mut()
+    }
+
+    /// Current size hints of the children.
+    pub fn size_hints(&self) -> Vec<(u32, u32)> {
+        self.items.iter().map(|c| c.widget.size_hint()).collect()
+    }
+
+    /// Whether the children changed or were resized since `arranged` was last called.
+    pub fn needs_arrange(&self) -> bool {
+        self.changed || self.hints != self.size_hints()
+    }
+
+    /// Remembers the current size hints for `needs_arrange` and returns them.
+    pub fn arranged(&mut self) -> Vec<(u32, u32)> {
+        self.hints = self.size_hints();
+        self.changed = false;
+        self.hints.clone()
+    }
+
+    pub fn draw(&mut self, canvas: &mut RefMut<Canvas<Window>>) {
+        for child in self.items.iter_mut() {
+            child.widget.draw(canvas);
+        }
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        for child in self.items.iter_mut() {
+            child.widget.tick(dt);
+        }
+    }
+
+    pub fn hit_test(&self, point: Point) -> bool {
+        self.items.iter().any(|c| c.widget.hit_test(point))
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.changed || self.items.iter().any(|c| c.widget.is_dirty())
+    }
+
+    pub fn widget(&self, index: usize) -> Option<&dyn Widget> {
+        self.items.get(index).map(|c| c.widget.as_ref())
+    }
+
+    pub fn widget_mut(&mut self, index: usize) -> Option<&mut (dyn Widget + 'static)> {
+        self.items.get_mut(index).map(|c| c.widget.as_mut())
+    }
+
+    /// Inserts before `index`, or last if `index` is past the end, like `Widget::insert_child`.
+    pub fn insert(&mut self, index: usize, widget: Box<dyn Widget>, placement: P) {
+        let index = index.min(self.items.len());
+        self.items.insert(index, Child { widget, placement });
+        self.changed = true;
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<Box<dyn Widget>> {
+        if index >= self.items.len() {
+            return None;
+        }
+        self.changed = true;
+        Some(self.items.remove(index).widget)
+    }
+
+    /// Swaps the widget at `index`, its placement stays.
+    pub fn replace(&mut self, index: usize, widget: Box<dyn Widget>) -> Option<Box<dyn Widget>> {
+        let child = self.items.get_mut(index)?;
+        self.changed = true;
+        Some(std::mem::replace(&mut child.widget, widget))
+    }
+
+    pub fn move_child(&mut self, from: usize, to: usize) -> Result<(), String> {
+        move_item(&mut self.items, from, to)?;
+        self.changed = true;
+        Ok(())
+    }
+}
+
+impl<P> Index<usize> for Children<P> {
+    type Output = Child<P>;
+
+    fn index(&self, index: usize) -> &Child<P> {
+        &self.items[index]
+    }
+}
+
+impl<P> Default for Children<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
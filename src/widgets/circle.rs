@@ -12,6 +12,7 @@ pub struct Circle {
     radius: i32,
     poly: Option<Polygon>,
     filled: bool,
+    id: Option<String>,
 }
 
 impl Circle {
@@ -23,6 +24,7 @@ impl Circle {
             radius,
             poly: None,
             filled: false,
+            id: None,
         }
     }
 
@@ -34,9 +36,15 @@ impl Circle {
             radius,
             poly: None,
             filled: true,
+            id: None,
         }
     }
 
+    pub fn id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
     fn get_circle(&self) -> Vec<Point> {
         let mut pxls = vec![];
         let radius = self.radius as i32;
@@ -107,4 +115,8 @@ impl Widget for Circle {
     fn get_rect(&self) -> sdl2::rect::Rect {
         sdl2::rect::Rect::new(self.x, self.y, self.radius as u32, self.radius as u32)
     }
+
+    fn get_id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
 }
\ No newline at end of file
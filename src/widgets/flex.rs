@@ -0,0 +1,299 @@
+use std::{cell::RefMut, marker::PhantomData};
+
+use sdl2::{
+    rect::{Point, Rect},
+    render::Canvas,
+    video::Window,
+};
+
+use super::{
+    children::Children,
+    layout::{Align, Justify, Padding},
+    Widget,
+};
+
+/// Main axis of a `Flex` container.
+pub trait Axis: Clone {
+    const HORIZONTAL: bool;
+}
+
+#[derive(Clone)]
+pub struct Horizontal;
+
+impl Axis for Horizontal {
+    const HORIZONTAL: bool = true;
+}
+
+#[derive(Clone)]
+pub struct Vertical;
+
+impl Axis for Vertical {
+    const HORIZONTAL: bool = false;
+}
+
+/// Places its children next to each other.
+pub type Row = Flex<Horizontal>;
+/// Places its children below each other.
+pub type Column = Flex<Vertical>;
+
+#[derive(Clone)]
+struct FlexFactors {
+    grow: f32,
+    shrink: f32,
+}
+
+/// Lines up children along the main axis at their size hints. Space left over is handed to
+/// children with a grow factor, or distributed by `justify` if there are none; missing space is
+/// taken from children in proportion to their shrink factor times their size.
+#[derive(Clone)]
+pub struct Flex<A: Axis> {
+    children: Children<FlexFactors>,
+    rect: Rect,
+    id: Option<String>,
+    spacing: u32,
+    padding: Padding,
+    justify: Justify,
+    align: Align,
+    dirty: bool,
+    axis: PhantomData<A>,
+}
+
+impl<A: Axis> Flex<A> {
+    pub fn new() -> Self {
+        Self {
+            children: Children::new(),
+            rect: Rect::new(0, 0, 0, 0),
+            id: None,
+            spacing: 0,
+            padding: Padding::default(),
+            justify: Justify::Start,
+            align: Align::Stretch,
+            dirty: true,
+            axis: PhantomData,
+        }
+    }
+
+    pub fn id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    /// Position and size when used as a root widget of an absolutely laid out window,
+    /// nested containers get theirs from the parent.
+    pub fn rect(mut self, x: i32, y: i32, width: u32, height: u32) -> Self {
+        self.rect = Rect::new(x, y, width, height);
+        self
+    }
+
+    /// Gap between two children.
+    pub fn spacing(mut self, spacing: u32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    pub fn padding<P: Into<Padding>>(mut self, padding: P) -> Self {
+        self.padding = padding.into();
+        self
+    }
+
+    /// Main axis alignment.
+    pub fn justify(mut self, justify: Justify) -> Self {
+        self.justify = justify;
+        self
+    }
+
+    /// Cross axis alignment.
+    pub fn align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Adds a child that keeps its size hint unless the container runs out of space.
+    pub fn add_widget(self, widget: Box<dyn Widget>) -> Self {
+        self.add_flex(widget, 0., 1.)
+    }
+
+    /// Adds a child that takes a `grow` share of the free space and gives up a `shrink` share
+    /// of the missing space.
+    pub fn add_flex(mut self, widget: Box<dyn Widget>, grow: f32, shrink: f32) -> Self {
+        self.children.push(widget, FlexFactors { grow, shrink });
+        self
+    }
+
+    /// Splits a size into its main and cross axis part, or joins the parts back into a size.
+    fn split((width, height): (u32, u32)) -> (u32, u32) {
+        if A::HORIZONTAL {
+            (width, height)
+        } else {
+            (height, width)
+        }
+    }
+
+    fn arrange(&mut self) {
+        let hints = self.children.arranged();
+        let inner = self.padding.shrink(self.rect);
+        let (main_space, cross_space) = Self::split((inner.width(), inner.height()));
+        let (main_start, cross_start) = if A::HORIZONTAL {
+            (inner.x(), inner.y())
+        } else {
+            (inner.y(), inner.x())
+        };
+
+        let count = self.children.len();
+        let gaps = self.spacing as f32 * count.saturating_sub(1) as f32;
+        let mut sizes: Vec<f32> = hints.iter().map(|h| Self::split(*h).0 as f32).collect();
+        let mut free = main_space as f32 - gaps - sizes.iter().sum::<f32>();
+
+        let grow: f32 = self.children.iter().map(|c| c.placement.grow.max(0.)).sum();
+        let shrink: f32 = self.children.iter().zip(&sizes).map(|(c, s)| c.placement.shrink.max(0.) * s).sum();
+        if free > 0. && grow > 0. {
+            for (size, child) in sizes.iter_mut().zip(self.children.iter()) {
+                *size += free * child.placement.grow.max(0.) / grow;
+            }
+            free = 0.;
+        } else if free < 0. && shrink > 0. {
+            for (size, child) in sizes.iter_mut().zip(self.children.iter()) {
+                *size = (*size + free * child.placement.shrink.max(0.) * *size / shrink).max(0.);
+            }
+            free = 0.;
+        }
+
+        let free = free.max(0.);
+        let (mut offset, between) = match self.justify {
+            Justify::Start => (0., 0.),
+            Justify::Center => (free / 2., 0.),
+            Justify::End => (free, 0.),
+            Justify::SpaceBetween if count > 1 => (0., free / (count - 1) as f32),
+            Justify::SpaceBetween => (0., 0.),
+            Justify::SpaceAround => (free / count.max(1) as f32 / 2., free / count.max(1) as f32),
+            Justify::SpaceEvenly => (free / (count + 1) as f32, free / (count + 1) as f32),
+        };
+
+        for (i, child) in self.children.iter_mut().enumerate() {
+            let (_, cross_hint) = Self::split(hints[i]);
+            let (cross_offset, cross_len) = self.align.place(cross_hint, cross_space);
+            let main = main_start + offset.round() as i32;
+            let main_len = (offset + sizes[i]).round() as i32 - offset.round() as i32;
+            let cross = cross_start + cross_offset;
+            let rect = if A::HORIZONTAL {
+                Rect::new(main, cross, main_len.max(0) as u32, cross_len)
+            } else {
+                Rect::new(cross, main, cross_len, main_len.max(0) as u32)
+            };
+            child.widget.set_rect(rect);
+            child.widget.layout(rect);
+            offset += sizes[i] + self.spacing as f32 + between;
+        }
+        self.dirty = true;
+    }
+}
+
+impl<A: Axis> Default for Flex<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A: Axis + 'static> Widget for Flex<A> {
+    fn draw(&mut self, canvas: &mut RefMut<Canvas<Window>>) {
+        if self.children.needs_arrange() {
+            self.arrange();
+        }
+        self.children.draw(canvas);
+        self.dirty = false;
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+        self.arrange();
+    }
+
+    fn get_rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// Only the children are hit, the container itself has nothing to click on.
+    fn hit_test(&self, point: Point) -> bool {
+        self.children.hit_test(point)
+    }
+
+    fn size_hint(&self) -> (u32, u32) {
+        let mut main = self.spacing * self.children.len().saturating_sub(1) as u32;
+        let mut cross = 0;
+        for hint in self.children.size_hints() {
+            let (m, c) = Self::split(hint);
+            main += m;
+            cross = cross.max(c);
+        }
+        let (main_padding, cross_padding) = Self::split((self.padding.horizontal(), self.padding.vertical()));
+        Self::split((main + main_padding, cross + cross_padding))
+    }
+
+    fn layout(&mut self, _bounds: Rect) {
+        self.arrange();
+    }
+
+    fn tick(&mut self, dt: f32) {
+        self.children.tick(dt);
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty || self.children.is_dirty()
+    }
+
+    fn child_count(&self) -> usize {
+        self.children.len()
+    }
+
+    fn child(&self, index: usize) -> Option<&dyn Widget> {
+        self.children.widget(index)
+    }
+
+    fn child_mut(&mut self, index: usize) -> Option<&mut (dyn Widget + 'static)> {
+        self.children.widget_mut(index)
+    }
+
+    fn insert_child(&mut self, index: usize, widget: Box<dyn Widget>) -> Result<(), String> {
+        self.children.insert(index, widget, FlexFactors { grow: 0., shrink: 1. });
+        Ok(())
+    }
+
+    fn remove_child(&mut self, index: usize) -> Option<Box<dyn Widget>> {
+        self.children.remove(index)
+    }
+
+    fn replace_child(&mut self, index: usize, widget: Box<dyn Widget>) -> Option<Box<dyn Widget>> {
+        self.children.replace(index, widget)
+    }
+
+    fn move_child(&mut self, from: usize, to: usize) -> Result<(), String> {
+        self.children.move_child(from, to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widgets::list::List;
+
+    fn heights(column: &Column) -> Vec<u32> {
+        (0..column.child_count()).map(|i| column.child(i).unwrap().get_rect().height()).collect()
+    }
+
+    #[test]
+    fn sizes_follow_hints_not_previous_layout() {
+        let mut column = Column::new()
+            .add_flex(Box::new(List::new(0, 0, 50, 20)), 1., 1.)
+            .add_flex(Box::new(List::new(0, 0, 50, 20)), 0., 1.);
+        column.set_rect(Rect::new(0, 0, 50, 100));
+        assert_eq!(heights(&column), [80, 20]);
+        column.set_rect(Rect::new(0, 0, 50, 40));
+        assert_eq!(heights(&column), [20, 20]);
+        column.set_rect(Rect::new(0, 0, 50, 100));
+        assert_eq!(heights(&column), [80, 20]);
+    }
+}
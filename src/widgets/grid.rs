@@ -0,0 +1,380 @@
+use std::cell::RefMut;
+
+use sdl2::{
+    rect::{Point, Rect},
+    render::Canvas,
+    video::Window,
+};
+
+use super::{
+    children::Children,
+    layout::{Align, Padding},
+    Widget,
+};
+
+/// Size of a grid row or column.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Track {
+    Fixed(u32),
+    /// As large as the largest size hint of the children in it.
+    Auto,
+    /// A share of the space left by the fixed and auto tracks, weighted by the factor.
+    Fr(f32),
+}
+
+/// Where a child goes in a `Grid`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GridCell {
+    pub row: usize,
+    pub column: usize,
+    pub row_span: usize,
+    pub column_span: usize,
+    /// Overrides the alignment of the grid for this cell.
+    pub align: Option<(Align, Align)>,
+}
+
+impl GridCell {
+    pub fn at(row: usize, column: usize) -> Self {
+        Self {
+            row,
+            column,
+            row_span: 1,
+            column_span: 1,
+            align: None,
+        }
+    }
+
+    pub fn span(mut self, rows: usize, columns: usize) -> Self {
+        self.row_span = rows.max(1);
+        self.column_span = columns.max(1);
+        self
+    }
+
+    pub fn align(mut self, horizontal: Align, vertical: Align) -> Self {
+        self.align = Some((horizontal, vertical));
+        self
+    }
+}
+
+/// Places children into the cells of a table of rows and columns.
+/// Rows and columns used by a child but not declared are `Track::Auto`.
+#[derive(Clone)]
+pub struct Grid {
+    children: Children<GridCell>,
+    rows: Vec<Track>,
+    columns: Vec<Track>,
+    rect: Rect,
+    id: Option<String>,
+    row_gap: u32,
+    column_gap: u32,
+    padding: Padding,
+    horizontal: Align,
+    vertical: Align,
+    dirty: bool,
+}
+
+impl Grid {
+    pub fn new() -> Self {
+        Self {
+            children: Children::new(),
+            rows: vec![],
+            columns: vec![],
+            rect: Rect::new(0, 0, 0, 0),
+            id: None,
+            row_gap: 0,
+            column_gap: 0,
+            padding: Padding::default(),
+            horizontal: Align::Stretch,
+            vertical: Align::Stretch,
+            dirty: true,
+        }
+    }
+
+    pub fn id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    /// Same as `Flex::rect`.
+    pub fn rect(mut self, x: i32, y: i32, width: u32, height: u32) -> Self {
+        self.rect = Rect::new(x, y, width, height);
+        self
+    }
+
+    pub fn rows(mut self, rows: Vec<Track>) -> Self {
+        self.rows = rows;
+        self
+    }
+
+    pub fn columns(mut self, columns: Vec<Track>) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// Space between two rows and between two columns.
+    pub fn gap(mut self, row_gap: u32, column_gap: u32) -> Self {
+        self.row_gap = row_gap;
+        self.column_gap = column_gap;
+        self
+    }
+
+    pub fn padding<P: Into<Padding>>(mut self, padding: P) -> Self {
+        self.padding = padding.into();
+        self
+    }
+
+    /// Default alignment of the children inside their cells, stretched on both axes by default.
+    pub fn align(mut self, horizontal: Align, vertical: Align) -> Self {
+        self.horizontal = horizontal;
+        self.vertical = vertical;
+        self
+    }
+
+    /// Spans of 0 are taken as 1, the fields of `GridCell` can be set directly.
+    pub fn add_widget(mut self, widget: Box<dyn Widget>, mut cell: GridCell) -> Self {
+        cell.row_span = cell.row_span.max(1);
+        cell.column_span = cell.column_span.max(1);
+        self.children.push(widget, cell);
+        self
+    }
+
+    /// Sizes of the column tracks, or of the row tracks if `!columns`, for `space` pixels and
+    /// the children's size `hints`. Without `space` fractional tracks are sized like auto tracks.
+    fn track_sizes(&self, hints: &[(u32, u32)], columns: bool, space: Option<u32>) -> Vec<u32> {
+        let (declared, gap) = if columns {
+            (&self.columns, self.column_gap)
+        } else {
+            (&self.rows, self.row_gap)
+        };
+        // (start, span, hinted size) of every child along this axis
+        let items: Vec<(usize, usize, u32)> = self
+            .children
+            .iter()
+            .zip(hints)
+            .map(|(c, (width, height))| {
+                if columns {
+                    (c.placement.column, c.placement.column_span, *width)
+                } else {
+                    (c.placement.row, c.placement.row_span, *height)
+                }
+            })
+            .collect();
+        let count = items.iter().map(|(start, span, _)| start + span).max().unwrap_or(0).max(declared.len());
+        let tracks: Vec<Track> = (0..count).map(|i| declared.get(i).copied().unwrap_or(Track::Auto)).collect();
+        let sized_by_content = |track: &Track| match track {
+            Track::Fixed(_) => false,
+            Track::Auto => true,
+            Track::Fr(_) => space.is_none(),
+        };
+
+        let mut sizes: Vec<u32> = tracks
+            .iter()
+            .map(|t| match t {
+                Track::Fixed(size) => *size,
+                _ => 0,
+            })
+            .collect();
+        for (start, _, size) in items.iter().filter(|(_, span, _)| *span == 1) {
+            if sized_by_content(&tracks[*start]) {
+                sizes[*start] = sizes[*start].max(*size);
+            }
+        }
+        // spanning children grow the content sized tracks they cover if they do not fit
+        for (start, span, size) in items.iter().filter(|(_, span, _)| *span > 1) {
+            let range = *start..start + span;
+            let current = sizes[range.clone()].iter().sum::<u32>() + gap * (*span as u32 - 1);
+            let growable: Vec<usize> = range.filter(|&i| sized_by_content(&tracks[i])).collect();
+            if *size > current && !growable.is_empty() {
+                let extra = (*size - current) as f32 / growable.len() as f32;
+                for i in growable {
+                    sizes[i] += extra.ceil() as u32;
+                }
+            }
+        }
+
+        if let Some(space) = space {
+            let used = sizes.iter().sum::<u32>() + gap * count.saturating_sub(1) as u32;
+            let free = space.saturating_sub(used) as f32;
+            let fr: f32 = tracks
+                .iter()
+                .map(|t| match t {
+                    Track::Fr(f) => f.max(0.),
+                    _ => 0.,
+                })
+                .sum();
+            if fr > 0. {
+                let mut offset = 0.;
+                for (size, track) in sizes.iter_mut().zip(&tracks) {
+                    if let Track::Fr(f) = track {
+                        // round the running total so the tracks add up to the free space
+                        let next = offset + free * f.max(0.) / fr;
+                        *size = next.round() as u32 - offset.round() as u32;
+                        offset = next;
+                    }
+                }
+            }
+        }
+        sizes
+    }
+
+    fn arrange(&mut self) {
+        let hints = self.children.arranged();
+        let inner = self.padding.shrink(self.rect);
+        let columns = self.track_sizes(&hints, true, Some(inner.width()));
+        let rows = self.track_sizes(&hints, false, Some(inner.height()));
+        let starts = |sizes: &[u32], gap: u32, origin: i32| -> Vec<i32> {
+            let mut offset = origin;
+            sizes
+                .iter()
+                .map(|size| {
+                    let start = offset;
+                    offset += (size + gap) as i32;
+                    start
+                })
+                .collect()
+        };
+        let column_starts = starts(&columns, self.column_gap, inner.x());
+        let row_starts = starts(&rows, self.row_gap, inner.y());
+        let extent = |sizes: &[u32], gap: u32, start: usize, span: usize| -> u32 {
+            sizes[start..start + span].iter().sum::<u32>() + gap * (span as u32 - 1)
+        };
+
+        for (child, (width, height)) in self.children.iter_mut().zip(&hints) {
+            let cell = child.placement;
+            let cell_width = extent(&columns, self.column_gap, cell.column, cell.column_span);
+            let cell_height = extent(&rows, self.row_gap, cell.row, cell.row_span);
+            let (horizontal, vertical) = cell.align.unwrap_or((self.horizontal, self.vertical));
+            let (x, width) = horizontal.place(*width, cell_width);
+            let (y, height) = vertical.place(*height, cell_height);
+            let rect = Rect::new(column_starts[cell.column] + x, row_starts[cell.row] + y, width, height);
+            child.widget.set_rect(rect);
+            child.widget.layout(rect);
+        }
+        self.dirty = true;
+    }
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Widget for Grid {
+    fn draw(&mut self, canvas: &mut RefMut<Canvas<Window>>) {
+        if self.children.needs_arrange() {
+            self.arrange();
+        }
+        self.children.draw(canvas);
+        self.dirty = false;
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+        self.arrange();
+    }
+
+    fn get_rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn hit_test(&self, point: Point) -> bool {
+        self.children.hit_test(point)
+    }
+
+    fn size_hint(&self) -> (u32, u32) {
+        let hints = self.children.size_hints();
+        let total = |sizes: Vec<u32>, gap: u32| sizes.iter().sum::<u32>() + gap * sizes.len().saturating_sub(1) as u32;
+        (
+            total(self.track_sizes(&hints, true, None), self.column_gap) + self.padding.horizontal(),
+            total(self.track_sizes(&hints, false, None), self.row_gap) + self.padding.vertical(),
+        )
+    }
+
+    fn layout(&mut self, _bounds: Rect) {
+        self.arrange();
+    }
+
+    fn tick(&mut self, dt: f32) {
+        self.children.tick(dt);
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty || self.children.is_dirty()
+    }
+
+    fn child_count(&self) -> usize {
+        self.children.len()
+    }
+
+    fn child(&self, index: usize) -> Option<&dyn Widget> {
+        self.children.widget(index)
+    }
+
+    fn child_mut(&mut self, index: usize) -> Option<&mut (dyn Widget + 'static)> {
+        self.children.widget_mut(index)
+    }
+
+    fn insert_child(&mut self, index: usize, widget: Box<dyn Widget>) -> Result<(), String> {
+        // without a cell the child goes into a new row below all others
+        let row = self.children.iter().map(|c| c.placement.row + c.placement.row_span).max().unwrap_or(0);
+        self.children.insert(index, widget, GridCell::at(row, 0));
+        Ok(())
+    }
+
+    fn remove_child(&mut self, index: usize) -> Option<Box<dyn Widget>> {
+        self.children.remove(index)
+    }
+
+    fn replace_child(&mut self, index: usize, widget: Box<dyn Widget>) -> Option<Box<dyn Widget>> {
+        self.children.replace(index, widget)
+    }
+
+    fn move_child(&mut self, from: usize, to: usize) -> Result<(), String> {
+        self.children.move_child(from, to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::stub::Stub;
+
+    fn rects(grid: &Grid) -> Vec<Rect> {
+        (0..grid.child_count()).map(|i| grid.child(i).unwrap().get_rect()).collect()
+    }
+
+    #[test]
+    fn spanning_child_grows_auto_tracks() {
+        let mut grid = Grid::new()
+            .gap(0, 10)
+            .add_widget(Stub::boxed(30, 20), GridCell::at(0, 0))
+            .add_widget(Stub::boxed(20, 20), GridCell::at(0, 1))
+            .add_widget(Stub::boxed(80, 20), GridCell::at(1, 0).span(1, 2));
+        // 30 + 10 + 20 leaves 20 missing for the spanning child, split over both columns
+        assert_eq!(grid.size_hint(), (80, 40));
+        grid.set_rect(Rect::new(0, 0, 80, 40));
+        let rects = rects(&grid);
+        assert_eq!(rects[0], Rect::new(0, 0, 40, 20));
+        assert_eq!(rects[1], Rect::new(50, 0, 30, 20));
+        assert_eq!(rects[2], Rect::new(0, 20, 80, 20));
+    }
+
+    #[test]
+    fn fractions_add_up_to_free_space() {
+        let mut grid = Grid::new().columns(vec![Track::Fr(1.), Track::Fr(1.), Track::Fr(1.)]);
+        for column in 0..3 {
+            grid = grid.add_widget(Stub::boxed(0, 10), GridCell::at(0, column));
+        }
+        grid.set_rect(Rect::new(0, 0, 100, 10));
+        let rects = rects(&grid);
+        let widths: Vec<u32> = rects.iter().map(|r| r.width()).collect();
+        assert_eq!(widths, [33, 34, 33]);
+        assert_eq!(rects[1].left(), rects[0].right());
+        assert_eq!(rects[2].right(), 100);
+    }
+}
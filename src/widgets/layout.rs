@@ -0,0 +1,91 @@
+use sdl2::rect::Rect;
+
+/// Placement of a child inside the space a container gives it along one axis.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Align {
+    Start,
+    Center,
+    End,
+    /// Fills the whole space.
+    Stretch,
+}
+
+impl Align {
+    /// Offset and length of an item of preferred length `hint` inside `space`.
+    pub fn place(self, hint: u32, space: u32) -> (i32, u32) {
+        let len = hint.min(space);
+        match self {
+            Align::Start => (0, len),
+            Align::Center => (((space - len) / 2) as i32, len),
+            Align::End => ((space - len) as i32, len),
+            Align::Stretch => (0, space),
+        }
+    }
+}
+
+/// Distribution of the space left along the main axis of a `Row` or `Column`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Justify {
+    Start,
+    Center,
+    End,
+    /// Equal gaps between the children, none at the edges.
+    SpaceBetween,
+    /// Equal space around every child, so the edges get half a gap.
+    SpaceAround,
+    /// Equal gaps between the children and at the edges.
+    SpaceEvenly,
+}
+
+/// Space between the edge of a container and its children.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Padding {
+    pub top: u32,
+    pub right: u32,
+    pub bottom: u32,
+    pub left: u32,
+}
+
+impl Padding {
+    pub fn all(padding: u32) -> Self {
+        Self {
+            top: padding,
+            right: padding,
+            bottom: padding,
+            left: padding,
+        }
+    }
+
+    pub fn symmetric(horizontal: u32, vertical: u32) -> Self {
+        Self {
+            top: vertical,
+            right: horizontal,
+            bottom: vertical,
+            left: horizontal,
+        }
+    }
+
+    /// `rect` without the padding, never smaller than zero.
+    pub fn shrink(&self, rect: Rect) -> Rect {
+        Rect::new(
+            rect.x() + self.left as i32,
+            rect.y() + self.top as i32,
+            rect.width().saturating_sub(self.left + self.right).max(1),
+            rect.height().saturating_sub(self.top + self.bottom).max(1),
+        )
+    }
+
+    pub fn horizontal(&self) -> u32 {
+        self.left + self.right
+    }
+
+    pub fn vertical(&self) -> u32 {
+        self.top + self.bottom
+    }
+}
+
+impl From<u32> for Padding {
+    fn from(padding: u32) -> Self {
+        Self::all(padding)
+    }
+}
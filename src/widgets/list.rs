@@ -4,12 +4,14 @@ use sdl2::{pixels::Color, rect::Rect, render::Canvas, video::Window};
 
 use crate::utils::style::Style;
 
-use super::{text::Text, Widget};
+use super::{move_item, text::Text, Widget};
 
 #[derive(Clone)]
 pub struct List {
     widgets: Vec<Box<dyn Widget>>,
     rect: Rect,
+    preferred: (u32, u32),
+    id: Option<String>,
     changed: bool,
     need_update: bool,
     viewport: Rect,
@@ -20,12 +22,19 @@ impl List {
         Self {
             widgets: vec![],
             rect: Rect::new(x, y, width, height),
+            preferred: (width, height),
+            id: None,
             changed: false,
             need_update: false,
             viewport: Rect::new(x, y, width, height),
         }
     }
 
+    pub fn id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
     pub fn add_widget(mut self, widget: Box<dyn Widget>) -> Self {
         // println!("Added widget {}: {:?}", self.widgets.len(), SystemTime::now());
         self.widgets.push(widget);
@@ -36,6 +45,11 @@ impl List {
     pub fn add_text(self, text: &str) -> Self {
         self.add_widget(Box::new(Text::new_to_zero(text, Style::new())))
     }
+
+    fn is_visible(viewport: Rect, widget: &dyn Widget) -> bool {
+        viewport.contains_point(widget.get_rect().bottom_left())
+            || viewport.contains_point(widget.get_rect().top_left())
+    }
 }
 
 impl Widget for List {
@@ -61,12 +75,9 @@ impl Widget for List {
         canvas.set_draw_color(Color::WHITE);
         let _ = canvas.fill_rect(self.rect);
 
+        let viewport = self.viewport;
         for (_i, widget) in self.widgets.iter_mut().enumerate() {
-            if self
-                .viewport
-                .contains_point(widget.get_rect().bottom_left())
-                || self.viewport.contains_point(widget.get_rect().top_left())
-            {
+            if Self::is_visible(viewport, widget.as_ref()) {
                 widget.draw(canvas);
             }
         }
@@ -83,6 +94,54 @@ impl Widget for List {
         }
     }
 
+    fn tick(&mut self, dt: f32) {
+        for widget in self.widgets.iter_mut() {
+            widget.tick(dt);
+        }
+    }
+
+    fn child_count(&self) -> usize {
+        self.widgets.len()
+    }
+
+    fn child(&self, index: usize) -> Option<&dyn Widget> {
+        self.widgets.get(index).map(|w| w.as_ref())
+    }
+
+    fn child_mut(&mut self, index: usize) -> Option<&mut (dyn Widget + 'static)> {
+        match self.widgets.get_mut(index) {
+            Some(widget) => Some(widget.as_mut()),
+            None => None,
+        }
+    }
+
+    fn insert_child(&mut self, index: usize, widget: Box<dyn Widget>) -> Result<(), String> {
+        let index = index.min(self.widgets.len());
+        self.widgets.insert(index, widget);
+        self.changed = true;
+        Ok(())
+    }
+
+    fn remove_child(&mut self, index: usize) -> Option<Box<dyn Widget>> {
+        if index >= self.widgets.len() {
+            return None;
+        }
+        self.changed = true;
+        Some(self.widgets.remove(index))
+    }
+
+    fn replace_child(&mut self, index: usize, widget: Box<dyn Widget>) -> Option<Box<dyn Widget>> {
+        let old = std::mem::replace(self.widgets.get_mut(index)?, widget);
+        self.changed = true;
+        Some(old)
+    }
+
+    fn move_child(&mut self, from: usize, to: usize) -> Result<(), String> {
+        move_item(&mut self.widgets, from, to)?;
+        self.changed = true;
+        Ok(())
+    }
+
     fn has_changed(&mut self) -> bool {
         if self.need_update {
             self.need_update = false;
@@ -91,6 +150,15 @@ impl Widget for List {
         false
     }
 
+    fn is_dirty(&self) -> bool {
+        // children outside the viewport are not drawn, so they cannot request a redraw either
+        self.changed
+            || self
+                .widgets
+                .iter()
+                .any(|w| Self::is_visible(self.viewport, w.as_ref()) && w.is_dirty())
+    }
+
     fn give_viewport(&mut self, viewport: Rect) {
         self.viewport = viewport;
     }
@@ -98,4 +166,12 @@ impl Widget for List {
     fn get_rect(&self) -> Rect {
         self.rect
     }
+
+    fn size_hint(&self) -> (u32, u32) {
+        self.preferred
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
 }
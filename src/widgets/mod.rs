@@ -1,26 +1,135 @@
-use std::cell::RefMut;
+use std::{any::Any, cell::RefMut};
 
 use dyn_clone::DynClone;
-use sdl2::{event::Event, rect::Rect, render::Canvas, video::Window};
+use sdl2::{
+    event::Event,
+    rect::{Point, Rect},
+    render::Canvas,
+    video::Window,
+};
 
-use crate::{window::MyWindow, Action};
+use crate::{
+    window::{context::WindowCtx, dispatch::EventCtx},
+    Action,
+};
 
+pub mod anchor;
 pub mod button;
+pub mod children;
+pub mod flex;
+pub mod grid;
+pub mod layout;
 pub mod list;
 pub mod scrollview;
+pub mod stack;
 pub mod text;
 pub mod circle;
 
-pub trait Widget: DynClone {
+/// Gives access to the concrete type behind a `dyn Widget`, see `MyWindow::find`.
+pub trait AsAny {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+pub trait Widget: DynClone + AsAny {
     fn draw(&mut self, canvas: &mut RefMut<Canvas<Window>>);
-    fn event(&mut self, _event: Event, _ctx: &MyWindow) -> Action {Action::None}
+    /// Handles an event routed to the widget or bubbling up from one of its children,
+    /// see `Dispatcher` for the routing rules.
+    fn event(&mut self, _event: Event, _ctx: &mut EventCtx) -> Action {Action::None}
+    /// Whether `point`, in logical window coordinates, lies on the widget.
+    fn hit_test(&self, point: Point) -> bool {
+        self.get_rect().contains_point(point)
+    }
+    /// Called when the pointer enters or leaves the widget or one of its children.
+    fn on_hover(&mut self, _hover: bool) {}
+    /// Whether the widget takes keyboard focus, by clicking it or with Tab.
+    fn focusable(&self) -> bool {
+        false
+    }
+    fn on_focus(&mut self) {}
+    fn on_blur(&mut self) {}
+    /// Containers expose their children so events can be routed to them.
+    fn child_count(&self) -> usize {
+        0
+    }
+    fn child(&self, _index: usize) -> Option<&dyn Widget> {
+        None
+    }
+    fn child_mut(&mut self, _index: usize) -> Option<&mut (dyn Widget + 'static)> {
+        None
+    }
+    /// Inserts a child before `index`, or last if `index` is past the end. Containers lay their
+    /// children out again on the next draw after any of these changes.
+    fn insert_child(&mut self, _index: usize, _widget: Box<dyn Widget>) -> Result<(), String> {
+        Err("widget cannot have children".to_string())
+    }
+    fn remove_child(&mut self, _index: usize) -> Option<Box<dyn Widget>> {
+        None
+    }
+    /// Puts `widget` in the place of the child at `index`, keeping its placement in the container,
+    /// and returns the old child.
+    fn replace_child(&mut self, _index: usize, _widget: Box<dyn Widget>) -> Option<Box<dyn Widget>> {
+        None
+    }
+    fn move_child(&mut self, _from: usize, _to: usize) -> Result<(), String> {
+        Err("widget cannot have children".to_string())
+    }
+    fn clear_children(&mut self) {
+        while self.remove_child(0).is_some() {}
+    }
     fn set_rect(&mut self, _rect: Rect);
     fn get_rect(&self) -> Rect;
+    /// Optional id to look the widget up with `MyWindow::find` or `Action::update_widget`.
+    fn get_id(&self) -> Option<&str> {
+        None
+    }
+    /// Preferred width and height, containers size their children from it. The default is the
+    /// current size, widgets that containers resize override it so the size they were given
+    /// does not become the size they ask for.
+    fn size_hint(&self) -> (u32, u32) {
+        let rect = self.get_rect();
+        (rect.width(), rect.height())
+    }
     fn has_changed(&mut self) -> bool {
         false
     }
+    /// Whether the widget changed visually since it was last drawn, windows only repaint when
+    /// one of their widgets is dirty. Widgets clear their own flag in `draw`.
+    fn is_dirty(&self) -> bool {
+        false
+    }
     fn give_viewport(&mut self, _viewport: Rect) {}
-    fn set_window(&mut self, _win: &mut MyWindow) {}
+    /// Lays the widget out inside `bounds`, the rect of its parent or of the window for root
+    /// widgets. Runs whenever the window is resized, absolutely positioned widgets ignore it.
+    fn layout(&mut self, _bounds: Rect) {}
+    /// Called when the widget is added to a window, directly or inside a container. Resources
+    /// tied to the window, like textures, are best created here.
+    fn on_mount(&mut self, _ctx: &WindowCtx) {}
+    /// Called when the widget is removed from its window or the window closes, the counterpart
+    /// of `on_mount` to release what it created.
+    fn on_unmount(&mut self) {}
+    /// Advances time based state such as animations, `dt` is the frame time in seconds.
+    fn tick(&mut self, _dt: f32) {}
 }
 
 dyn_clone::clone_trait_object!(Widget);
+
+/// Moves `items[from]` to `to`, shifting the items in between, for `Widget::move_child`.
+pub fn move_item<T>(items: &mut Vec<T>, from: usize, to: usize) -> Result<(), String> {
+    if from >= items.len() || to >= items.len() {
+        return Err(format!("cannot move child {} to {}, there are {}", from, to, items.len()));
+    }
+    let item = items.remove(from);
+    items.insert(to, item);
+    Ok(())
+}
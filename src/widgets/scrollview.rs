@@ -1,9 +1,10 @@
 use sdl2::{
+    event::Event,
     pixels::Color,
     rect::{Point, Rect},
 };
 
-use crate::{window::MyWindow, Action};
+use crate::{window::dispatch::EventCtx, Action};
 
 use super::Widget;
 
@@ -14,7 +15,10 @@ const NORMAL_FRICTION: f32 = 0.4;
 pub struct ScrollView {
     widget: Box<dyn Widget>,
     rect: Rect,
+    preferred: (u32, u32),
+    id: Option<String>,
     hover: bool,
+    dirty: bool,
     scrolling: bool,
     scroll_sensitivity: i32,
     scroll: f32,
@@ -42,7 +46,10 @@ impl ScrollView {
         let mut obj = Self {
             widget,
             rect: Rect::new(x, y, width, height),
+            preferred: (width, height),
+            id: None,
             hover: false,
+            dirty: true,
             scrolling: false,
             scroll_sensitivity: 40,
             scroll: 0.,
@@ -74,18 +81,31 @@ impl ScrollView {
         obj
     }
 
+    pub fn id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    /// How far the content is scrolled down, in pixels.
+    pub fn scroll(&self) -> f32 {
+        self.scroll
+    }
+
     pub fn update(&mut self) {
         let mut w_rect = self.widget.get_rect();
         let t_rect = self.rect;
 
-        w_rect.set_x(t_rect.x());
-        w_rect.set_y(t_rect.y());
-        self.widget.set_rect(w_rect);
-
         let ratio = (t_rect.height() as f32 / w_rect.height() as f32).min(1.);
 
         self.v_ratio = ratio;
-        println!("{}", self.v_ratio);
+        // keep the scroll position inside the (possibly resized) scroll range
+        let max_scroll = (t_rect.height() as f32 / ratio - t_rect.height() as f32).max(0.);
+        self.scroll = self.scroll.clamp(0., max_scroll);
+
+        w_rect.set_x(t_rect.x());
+        w_rect.set_y(t_rect.y() - self.scroll as i32);
+        self.widget.set_rect(w_rect);
+
         self.widget.give_viewport(Rect::new(self.rect.x(), self.rect.y(), self.rect.width(), self.rect.height() + 100));
     }
 }
@@ -102,38 +122,6 @@ impl Widget for ScrollView {
             self.update();
         }
 
-        if self.scrolling {
-            // add specific scroll friction
-            if cfg!(target_os = "macos") {
-                self.scroll_acceleration *= APPLE_FRICTION;
-            } else {
-                self.scroll_acceleration *= NORMAL_FRICTION;
-            }
-
-            if self.scroll_acceleration.abs() < 0.0005 {
-                self.scroll_acceleration = 0.;
-                self.scrolling = false;
-            }
-            self.scroll -= self.scroll_sensitivity as f32 * self.scroll_acceleration;
-            // Here you have to set your scrolling bounds i.e. if(scroll_Y < 0) scroll_Y = 0;
-            if self.scroll < 0. {
-                // if at the top clamp
-                self.scroll = 0.;
-                self.scroll_acceleration = 0.;
-            } else if self.scroll + self.rect.height() as f32
-                >= self.rect.height() as f32 / self.v_ratio
-            // at the bottom clamp too
-            {
-                self.scroll =
-                    (self.rect.height() as f32 / self.v_ratio) - self.rect.height() as f32;
-                self.scroll_acceleration = 0.;
-            }
-
-            let mut w_rect = self.widget.get_rect();
-            w_rect.set_y(self.rect.y - self.scroll as i32); // apply scroll to the widget
-            self.widget.set_rect(w_rect);
-        }
-
         canvas.set_clip_rect(self.rect);
         self.widget.draw(canvas); // draw widget
         canvas.set_clip_rect(None);
@@ -161,78 +149,155 @@ impl Widget for ScrollView {
             );
             let _ = canvas.fill_rect(self.scroll_thumb_rect);
         }
+        self.dirty = false;
     }
 
-    fn event(&mut self, event: sdl2::event::Event, win: &MyWindow) -> Action {
+    fn event(&mut self, event: Event, ctx: &mut EventCtx) -> Action {
         match event {
-            sdl2::event::Event::MouseMotion {
-                window_id,
-                x,
-                y,
-                yrel,
-                ..
-            } => {
-                if window_id == win.get_id() {
-                    let mouse = Point::new(x, y);
-                    self.hover = self.rect.contains_point(mouse);
-                    self.thumb_hover = self.scroll_thumb_rect.contains_point(mouse);
-                    if self.v_ratio < 1. && self.drag_thumb {
-                        self.scroll += yrel as f32 / self.v_ratio;
-                        self.scrolling = true;
-                        if mouse.y() <= self.rect.y() + self.drag_offset {
-                            self.scroll = 0.;
-                        } else if mouse.y() >= self.rect.height() as i32 - self.drag_offset {
-                            self.scroll = self.rect.height() as f32 / self.v_ratio;
-                        }
-                    }
-                    if self.v_ratio < 1. && self.scroll_area_rect.contains_point(mouse)
-                        || self.drag_thumb
-                    {
-                        self.scroll_area_width = 10;
-                    } else {
-                        self.scroll_area_width = 8;
-                    }
-                    if self.hover {
-                        self.widget.event(event, win);
+            Event::MouseMotion { x, y, yrel, .. } => {
+                let mouse = Point::new(x, y);
+                let scroll_area_width = self.scroll_area_width;
+                let thumb_hover = self.thumb_hover;
+                self.thumb_hover = self.scroll_thumb_rect.contains_point(mouse);
+                if self.v_ratio < 1. && self.drag_thumb {
+                    self.scroll += yrel as f32 / self.v_ratio;
+                    self.scrolling = true;
+                    if mouse.y() <= self.rect.y() + self.drag_offset {
+                        self.scroll = 0.;
+                    } else if mouse.y() >= self.rect.height() as i32 - self.drag_offset {
+                        self.scroll = self.rect.height() as f32 / self.v_ratio;
                     }
                 }
-            }
-            sdl2::event::Event::MouseButtonDown {
-                window_id, x, y, ..
-            } => {
-                if  window_id == win.get_id() {
-                    self.widget.event(event, win);
-                    if self.hover && self.scroll_thumb_rect.contains_point(Point::new(x, y)) {
-                        self.drag_thumb = true;
-                        self.drag_offset = y - self.scroll_thumb_rect.y();
-                    }
+                if self.v_ratio < 1. && self.scroll_area_rect.contains_point(mouse)
+                    || self.drag_thumb
+                {
+                    self.scroll_area_width = 10;
+                } else {
+                    self.scroll_area_width = 8;
                 }
-            }
-            sdl2::event::Event::MouseButtonUp { .. } => {
-                if self.drag_thumb {
-                    self.drag_thumb = false;
+                if scroll_area_width != self.scroll_area_width || thumb_hover != self.thumb_hover {
+                    self.dirty = true;
                 }
             }
-            sdl2::event::Event::MouseWheel {
-                window_id,
-                precise_y,
-                ..
-            } => {
-                if self.hover && window_id == win.get_id() {
-                    self.scroll_acceleration += precise_y;
-                    self.scrolling = true;
-                }
+            Event::MouseButtonDown { x, y, .. } if self.scroll_thumb_rect.contains_point(Point::new(x, y)) => {
+                self.drag_thumb = true;
+                self.drag_offset = y - self.scroll_thumb_rect.y();
+                ctx.set_handled();
+            }
+            Event::MouseButtonUp { .. } if self.drag_thumb => {
+                self.drag_thumb = false;
+                ctx.set_handled();
+            }
+            // only the innermost scroll view scrolls
+            Event::MouseWheel { precise_y, .. } if self.v_ratio < 1. => {
+                self.scroll_acceleration += precise_y;
+                self.scrolling = true;
+                ctx.set_handled();
             }
             _ => {}
         }
         Action::None
     }
 
+    fn on_hover(&mut self, hover: bool) {
+        self.hover = hover;
+        if !hover && !self.drag_thumb && (self.thumb_hover || self.scroll_area_width != 8) {
+            self.thumb_hover = false;
+            self.scroll_area_width = 8;
+            self.dirty = true;
+        }
+    }
+
+    fn child_count(&self) -> usize {
+        1
+    }
+
+    fn child(&self, index: usize) -> Option<&dyn Widget> {
+        (index == 0).then(|| self.widget.as_ref())
+    }
+
+    fn child_mut(&mut self, index: usize) -> Option<&mut (dyn Widget + 'static)> {
+        match index {
+            0 => Some(self.widget.as_mut()),
+            _ => None,
+        }
+    }
+
+    /// The content can be swapped out but not removed, the scroll position is kept as far as
+    /// the new content allows.
+    fn replace_child(&mut self, index: usize, widget: Box<dyn Widget>) -> Option<Box<dyn Widget>> {
+        if index != 0 {
+            return None;
+        }
+        let old = std::mem::replace(&mut self.widget, widget);
+        self.widget.layout(self.rect);
+        self.update();
+        self.dirty = true;
+        Some(old)
+    }
+
+    fn tick(&mut self, dt: f32) {
+        self.widget.tick(dt);
+
+        if self.scrolling {
+            // the friction constants are tuned per frame at 60 fps
+            let frames = dt * 60.;
+            // add specific scroll friction
+            if cfg!(target_os = "macos") {
+                self.scroll_acceleration *= APPLE_FRICTION.powf(frames);
+            } else {
+                self.scroll_acceleration *= NORMAL_FRICTION.powf(frames);
+            }
+
+            if self.scroll_acceleration.abs() < 0.0005 {
+                self.scroll_acceleration = 0.;
+                self.scrolling = false;
+            }
+            self.scroll -= self.scroll_sensitivity as f32 * self.scroll_acceleration * frames;
+            // Here you have to set your scrolling bounds i.e. if(scroll_Y < 0) scroll_Y = 0;
+            if self.scroll < 0. {
+                // if at the top clamp
+                self.scroll = 0.;
+                self.scroll_acceleration = 0.;
+            } else if self.scroll + self.rect.height() as f32
+                >= self.rect.height() as f32 / self.v_ratio
+            // at the bottom clamp too
+            {
+                self.scroll =
+                    (self.rect.height() as f32 / self.v_ratio) - self.rect.height() as f32;
+                self.scroll_acceleration = 0.;
+            }
+
+            let mut w_rect = self.widget.get_rect();
+            w_rect.set_y(self.rect.y - self.scroll as i32); // apply scroll to the widget
+            self.widget.set_rect(w_rect);
+        }
+    }
+
     fn set_rect(&mut self, rect: Rect) {
         self.rect = rect;
+        self.update();
+        self.dirty = true;
+    }
+
+    fn layout(&mut self, _bounds: Rect) {
+        self.widget.layout(self.rect);
+        self.update();
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty || self.scrolling || self.widget.is_dirty()
     }
 
     fn get_rect(&self) -> Rect {
         self.rect
     }
+
+    fn size_hint(&self) -> (u32, u32) {
+        self.preferred
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
 }
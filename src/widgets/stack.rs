@@ -0,0 +1,163 @@
+use std::cell::RefMut;
+
+use sdl2::{
+    rect::{Point, Rect},
+    render::Canvas,
+    video::Window,
+};
+
+use super::{
+    children::Children,
+    layout::{Align, Padding},
+    Widget,
+};
+
+/// Places its children on top of each other, later children above earlier ones.
+/// Children are aligned inside the padded rect on both axes, stretched by default.
+#[derive(Clone)]
+pub struct Stack {
+    children: Children<()>,
+    rect: Rect,
+    id: Option<String>,
+    padding: Padding,
+    horizontal: Align,
+    vertical: Align,
+    dirty: bool,
+}
+
+impl Stack {
+    pub fn new() -> Self {
+        Self {
+            children: Children::new(),
+            rect: Rect::new(0, 0, 0, 0),
+            id: None,
+            padding: Padding::default(),
+            horizontal: Align::Stretch,
+            vertical: Align::Stretch,
+            dirty: true,
+        }
+    }
+
+    pub fn id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    /// Same as `Flex::rect`.
+    pub fn rect(mut self, x: i32, y: i32, width: u32, height: u32) -> Self {
+        self.rect = Rect::new(x, y, width, height);
+        self
+    }
+
+    pub fn padding<P: Into<Padding>>(mut self, padding: P) -> Self {
+        self.padding = padding.into();
+        self
+    }
+
+    pub fn align(mut self, horizontal: Align, vertical: Align) -> Self {
+        self.horizontal = horizontal;
+        self.vertical = vertical;
+        self
+    }
+
+    pub fn add_widget(mut self, widget: Box<dyn Widget>) -> Self {
+        self.children.push(widget, ());
+        self
+    }
+
+    fn arrange(&mut self) {
+        let hints = self.children.arranged();
+        let inner = self.padding.shrink(self.rect);
+        for (child, (width, height)) in self.children.iter_mut().zip(&hints) {
+            let (x, width) = self.horizontal.place(*width, inner.width());
+            let (y, height) = self.vertical.place(*height, inner.height());
+            let rect = Rect::new(inner.x() + x, inner.y() + y, width, height);
+            child.widget.set_rect(rect);
+            child.widget.layout(rect);
+        }
+        self.dirty = true;
+    }
+}
+
+impl Default for Stack {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Widget for Stack {
+    fn draw(&mut self, canvas: &mut RefMut<Canvas<Window>>) {
+        if self.children.needs_arrange() {
+            self.arrange();
+        }
+        self.children.draw(canvas);
+        self.dirty = false;
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+        self.arrange();
+    }
+
+    fn get_rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn hit_test(&self, point: Point) -> bool {
+        self.children.hit_test(point)
+    }
+
+    fn size_hint(&self) -> (u32, u32) {
+        let (width, height) = self
+            .children
+            .size_hints()
+            .into_iter()
+            .fold((0, 0), |(w, h), (cw, ch)| (w.max(cw), h.max(ch)));
+        (width + self.padding.horizontal(), height + self.padding.vertical())
+    }
+
+    fn layout(&mut self, _bounds: Rect) {
+        self.arrange();
+    }
+
+    fn tick(&mut self, dt: f32) {
+        self.children.tick(dt);
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty || self.children.is_dirty()
+    }
+
+    fn child_count(&self) -> usize {
+        self.children.len()
+    }
+
+    fn child(&self, index: usize) -> Option<&dyn Widget> {
+        self.children.widget(index)
+    }
+
+    fn child_mut(&mut self, index: usize) -> Option<&mut (dyn Widget + 'static)> {
+        self.children.widget_mut(index)
+    }
+
+    fn insert_child(&mut self, index: usize, widget: Box<dyn Widget>) -> Result<(), String> {
+        self.children.insert(index, widget, ());
+        Ok(())
+    }
+
+    fn remove_child(&mut self, index: usize) -> Option<Box<dyn Widget>> {
+        self.children.remove(index)
+    }
+
+    fn replace_child(&mut self, index: usize, widget: Box<dyn Widget>) -> Option<Box<dyn Widget>> {
+        self.children.replace(index, widget)
+    }
+
+    fn move_child(&mut self, from: usize, to: usize) -> Result<(), String> {
+        self.children.move_child(from, to)
+    }
+}
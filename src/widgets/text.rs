@@ -1,5 +1,5 @@
 use std::{
-    cell::{RefCell, RefMut},
+    cell::{Cell, RefCell, RefMut},
     env,
     path::{Path, PathBuf},
     rc::Rc, collections::HashMap,
@@ -8,12 +8,12 @@ use std::{
 use lazy_static::lazy_static;
 use sdl2::{
     rect::Rect,
-    render::{Canvas, TextureQuery},
+    render::{Canvas, Texture, TextureCreator, TextureQuery},
     surface::Surface,
-    video::Window,
+    video::{Window, WindowContext},
 };
 
-use crate::{TTF_CONTEXT, utils::style::{Style, FontStyle, StyleValues, TextAlign}};
+use crate::{TTF_CONTEXT, window::context::WindowCtx, state::{Binding, Signal}, utils::style::{Style, FontStyle, StyleValues, TextAlign}};
 
 use super::Widget;
 
@@ -33,12 +33,71 @@ lazy_static! {
 
 add_new_to_zero_with_lifetime!(Text, text: &str, style: Style);
 
+/// The texture creator of the window the text is mounted in, with the rasterized text uploaded
+/// through it. Holding the creator keeps the window's renderer alive, so the texture is always
+/// destroyed before it. Clones are not mounted anywhere.
+#[derive(Default)]
+struct Mount {
+    creator: Option<Rc<TextureCreator<WindowContext>>>,
+    texture: Option<Texture>,
+}
+
+impl Mount {
+    fn new(creator: Rc<TextureCreator<WindowContext>>) -> Self {
+        Self {
+            creator: Some(creator),
+            texture: None,
+        }
+    }
+
+    /// Does nothing while unmounted, there is no renderer to upload to.
+    fn upload(&mut self, surface: &Surface) -> Result<(), String> {
+        if let Some(creator) = &self.creator {
+            let texture = creator.create_texture_from_surface(surface).map_err(|e| e.to_string())?;
+            self.discard();
+            self.texture = Some(texture);
+        }
+        Ok(())
+    }
+
+    fn discard(&mut self) {
+        if let Some(texture) = self.texture.take() {
+            // SAFETY: the texture was created by `creator`, which is still held and keeps its
+            // renderer alive
+            unsafe { texture.destroy() };
+        }
+    }
+}
+
+impl Clone for Mount {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl Drop for Mount {
+    fn drop(&mut self) {
+        self.discard();
+    }
+}
+
 #[derive(Clone)]
 pub struct Text<'a> {
     text: String,
     rect: Rect,
+    id: Option<String>,
     texture: Option<Rc<RefCell<Surface<'a>>>>,
+    mount: Mount,
     style: Style,
+    /// Render scale the texture was rasterized for.
+    raster_scale: f32,
+    /// Horizontal offset applied to `rect` for the text alignment.
+    align_offset: i32,
+    /// Size given to `clipped`, without it the width follows the text.
+    clip: Option<(u32, u32)>,
+    binding: Option<Binding>,
+    /// Size of the text at its font size, measured on demand.
+    measured: Cell<Option<(u32, u32)>>,
 }
 
 impl<'a> Text<'a> {
@@ -46,8 +105,15 @@ impl<'a> Text<'a> {
         let mut s = Self {
             text: text.replace('\t', "    "),
             rect: Rect::new(x, y, 0, 0),
+            id: None,
             texture: None,
+            mount: Mount::default(),
             style,
+            raster_scale: 1.,
+            align_offset: 0,
+            clip: None,
+            binding: None,
+            measured: Cell::new(None),
         };
         s.update_height();
         s
@@ -57,39 +123,92 @@ impl<'a> Text<'a> {
         let mut s = Self {
             text: text.replace('\t', "    "),
             rect: Rect::new(x, y, width, height),
+            id: None,
             texture: None,
+            mount: Mount::default(),
             style,
+            raster_scale: 1.,
+            align_offset: 0,
+            clip: None,
+            binding: None,
+            measured: Cell::new(None),
         };
         s.update_height();
+        s.clip = Some((s.rect.width(), s.rect.height()));
         s
     }
 
+    /// Shows the value of `signal`, formatted by `format`, and follows its changes.
+    pub fn bind<T: 'static, F: 'static + Fn(&T) -> String>(mut self, signal: &Signal<T>, format: F) -> Self {
+        let mut binding = Binding::new(signal, format);
+        self.set_text(&binding.take());
+        self.binding = Some(binding);
+        self
+    }
+
+    pub fn id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.replace('\t', "    ");
+        self.texture = None;
+        self.measured.set(None);
+        if self.clip.is_none() {
+            // let the next rasterization measure the new text
+            self.rect.set_width(0);
+        }
+    }
+
+    /// Moves the text so the point its alignment refers to, the left edge, center or right edge
+    /// of the text, lies at `x`.
+    pub fn set_anchor(&mut self, x: i32, y: i32) {
+        self.rect.set_x(x - self.align_offset);
+        self.rect.set_y(y);
+    }
+
     fn get_style(&self) -> &StyleValues {
         &self.style.normal
     }
 
-    fn update_texture(&mut self) {
+    /// Rasterizes the text for a render `scale`, the rect stays in logical units.
+    fn update_texture(&mut self, scale: f32) {
+        let font_size = (self.get_style().font_size as f32 * scale).round() as u16;
         let mut font = TTF_CONTEXT
-            .load_font(Path::new(&FONT_PATHS.get(&self.get_style().font_style).unwrap().as_os_str()), self.get_style().font_size)
+            .load_font(Path::new(&FONT_PATHS.get(&self.get_style().font_style).unwrap().as_os_str()), font_size)
             .unwrap();
         font.set_style(sdl2::ttf::FontStyle::BOLD);
-        let surface = font.render(&self.text).blended(self.get_style().text_color).unwrap();
+        // SDL_ttf cannot render empty strings
+        let text = if self.text.is_empty() { " " } else { &self.text };
+        let surface = font.render(text).blended(self.get_style().text_color).unwrap();
+        let width = (surface.rect().width() as f32 / scale) as u32;
+        let height = (surface.rect().height() as f32 / scale) as u32;
         if self.rect.width() <= 4 {
-            self.rect.set_width(surface.rect().width());
+            self.rect.set_width(width);
         }
         if self.rect.height() <= 4 {
-            self.rect.set_height(surface.rect().height());
+            self.rect.set_height(height);
         }
-        match self.get_style().text_align {
-            TextAlign::Center => {
-                self.rect.set_x(self.rect.x() - surface.rect().width() as i32 / 2);
-            }
-            TextAlign::Left => {}
-            TextAlign::Right => {
-                self.rect.set_x(self.rect.x() - surface.rect().width() as i32);
+        // undo the offset of a previous rasterization before aligning again
+        self.rect.set_x(self.rect.x() + self.align_offset);
+        self.align_offset = match self.get_style().text_align {
+            TextAlign::Center => width as i32 / 2,
+            TextAlign::Left => 0,
+            TextAlign::Right => width as i32,
+        };
+        self.rect.set_x(self.rect.x() - self.align_offset);
+        self.raster_scale = scale;
+        self.texture = Some(Rc::new(RefCell::new(surface)));
+        self.mount.discard();
+    }
+
+    fn upload(&mut self) {
+        if let Some(surface) = &self.texture {
+            if let Err(e) = self.mount.upload(&surface.borrow()) {
+                eprintln!("Could not upload text \"{}\": {}", self.text, e);
             }
         }
-        self.texture = Some(Rc::new(RefCell::new(surface)));
     }
 
     fn update_height(&mut self) {
@@ -103,28 +222,85 @@ impl<'a> Text<'a> {
     }
 }
 
-impl<'a> Widget for Text<'a> {
+impl Widget for Text<'static> {
 
     fn draw(&mut self, canvas: &mut RefMut<Canvas<Window>>) {
-        if self.texture.is_some() {
-            let texture_creator = canvas.texture_creator();
-            let texture = texture_creator
-                .create_texture_from_surface(&*(self.texture.clone().unwrap().borrow()))
-                .unwrap();
+        if let Some(text) = self.binding.as_mut().filter(|b| b.changed()).map(|b| b.take()) {
+            self.set_text(&text);
+        }
+        let scale = canvas.scale().0;
+        if self.texture.is_none() || self.raster_scale != scale {
+            self.update_texture(scale);
+        }
+        // texts that changed since they were mounted upload on first draw
+        if self.mount.texture.is_none() {
+            self.upload();
+        }
+        if let Some(texture) = &self.mount.texture {
             let TextureQuery { width, height, .. } = texture.query();
             let _ratio = width as f32 / height as f32;
-            
-            canvas.copy(&texture, None, Some(Rect::new(self.rect.x(), self.rect.y(), width, height))).unwrap();
-        } else {
-            self.update_texture();
+            let width = (width as f32 / scale) as u32;
+            let height = (height as f32 / scale) as u32;
+
+            canvas.copy(texture, None, Some(Rect::new(self.rect.x(), self.rect.y(), width, height))).unwrap();
         }
     }
 
+    /// Keeps the window's texture creator for uploads and only uploads what is already
+    /// rasterized. Rasterizing loads the font, so it waits for the first draw, which texts
+    /// scrolled out of view may never get.
+    fn on_mount(&mut self, ctx: &WindowCtx) {
+        self.mount = Mount::new(ctx.shared_texture_creator());
+        if self.texture.is_some() && self.raster_scale == ctx.scale_factor() {
+            self.upload();
+        }
+    }
+
+    fn on_unmount(&mut self) {
+        self.mount = Mount::default();
+    }
+
+    /// Aligns the text inside `rect` the way a button aligns its label. Texts without a fixed
+    /// size keep their measured width and height.
     fn set_rect(&mut self, rect: Rect) {
-        self.rect = rect;
+        let x = match self.get_style().text_align {
+            TextAlign::Center => rect.x() + rect.width() as i32 / 2,
+            TextAlign::Left => rect.x(),
+            TextAlign::Right => rect.x() + rect.width() as i32,
+        };
+        if self.clip.is_some() {
+            self.rect.set_width(rect.width());
+            self.rect.set_height(rect.height());
+        }
+        self.set_anchor(x, rect.y());
     }
 
     fn get_rect(&self) -> Rect {
         self.rect
     }
+
+    fn get_id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn size_hint(&self) -> (u32, u32) {
+        if let Some(size) = self.clip {
+            return size;
+        }
+        if let Some(size) = self.measured.get() {
+            return size;
+        }
+        let mut font = TTF_CONTEXT
+            .load_font(Path::new(&FONT_PATHS.get(&self.get_style().font_style).unwrap().as_os_str()), self.get_style().font_size)
+            .unwrap();
+        font.set_style(sdl2::ttf::FontStyle::BOLD);
+        let text = if self.text.is_empty() { " " } else { &self.text };
+        let size = font.size_of(text).unwrap_or((self.rect.width(), self.rect.height()));
+        self.measured.set(Some(size));
+        size
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.texture.is_none() || self.binding.as_ref().is_some_and(|b| b.changed())
+    }
 }
@@ -0,0 +1,218 @@
+use std::{cell::RefMut, path::PathBuf};
+
+use sdl2::{
+    surface::Surface,
+    sys::SDL_WindowFlags,
+    video::Window,
+    VideoSubsystem,
+};
+
+use crate::{widgets::Widget, CanvasCell};
+
+use super::{create_canvas, CanvasOptions, MyWindow, Renderer};
+
+#[derive(Clone, Copy)]
+enum Position {
+    Centered,
+    At(i32, i32),
+}
+
+/// Configures and creates a `MyWindow`, e.g.
+/// `WindowBuilder::new("Tools", 400, 300).resizable().min_size(200, 150).build(&video, draw)`.
+#[derive(Clone)]
+pub struct WindowBuilder {
+    title: String,
+    width: u32,
+    height: u32,
+    position: Position,
+    resizable: bool,
+    borderless: bool,
+    always_on_top: bool,
+    hidden: bool,
+    high_dpi: bool,
+    min_size: Option<(u32, u32)>,
+    max_size: Option<(u32, u32)>,
+    icon: Option<PathBuf>,
+    scale_factor: f32,
+    parent: Option<u32>,
+    modal: bool,
+    persistent: bool,
+    options: CanvasOptions,
+}
+
+impl WindowBuilder {
+    pub fn new(title: &str, width: u32, height: u32) -> Self {
+        Self {
+            title: title.to_string(),
+            width,
+            height,
+            position: Position::Centered,
+            resizable: false,
+            borderless: false,
+            always_on_top: false,
+            hidden: false,
+            high_dpi: false,
+            min_size: None,
+            max_size: None,
+            icon: None,
+            scale_factor: 1.,
+            parent: None,
+            modal: false,
+            persistent: false,
+            options: CanvasOptions::default(),
+        }
+    }
+
+    pub fn resizable(mut self) -> Self {
+        self.resizable = true;
+        self
+    }
+
+    pub fn borderless(mut self) -> Self {
+        self.borderless = true;
+        self
+    }
+
+    pub fn always_on_top(mut self) -> Self {
+        self.always_on_top = true;
+        self
+    }
+
+    /// Creates the window hidden, `MyWindow::show` makes it visible.
+    pub fn hidden(mut self) -> Self {
+        self.hidden = true;
+        self
+    }
+
+    pub fn high_dpi(mut self) -> Self {
+        self.high_dpi = true;
+        self
+    }
+
+    pub fn centered(mut self) -> Self {
+        self.position = Position::Centered;
+        self
+    }
+
+    /// Places the top left corner at screen coordinates.
+    pub fn position(mut self, x: i32, y: i32) -> Self {
+        self.position = Position::At(x, y);
+        self
+    }
+
+    /// Places the window at an offset from the top left corner of `parent`.
+    pub fn position_relative_to(mut self, parent: &MyWindow, x: i32, y: i32) -> Self {
+        let (px, py) = parent.position();
+        self.position = Position::At(px + x, py + y);
+        self
+    }
+
+    pub fn min_size(mut self, width: u32, height: u32) -> Self {
+        self.min_size = Some((width, height));
+        self
+    }
+
+    pub fn max_size(mut self, width: u32, height: u32) -> Self {
+        self.max_size = Some((width, height));
+        self
+    }
+
+    /// Loads the window icon from a BMP file when the window is built.
+    pub fn icon<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.icon = Some(path.into());
+        self
+    }
+
+    /// Initial user scale factor, see `MyWindow::set_scale_factor`.
+    pub fn scale_factor(mut self, scale: f32) -> Self {
+        self.scale_factor = scale;
+        self
+    }
+
+    /// Makes the window owned by the window with the SDL id `parent`, it closes with its parent.
+    pub fn parent(mut self, parent: u32) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    /// Blocks input to the parent window while this one is open and centers it on the parent.
+    pub fn modal(mut self) -> Self {
+        self.modal = true;
+        self
+    }
+
+    /// Restores the window's last geometry when it is registered with a key and saves it on close.
+    pub fn persistent(mut self) -> Self {
+        self.persistent = true;
+        self
+    }
+
+    pub fn renderer(mut self, renderer: Renderer) -> Self {
+        self.options.renderer = renderer;
+        self
+    }
+
+    pub fn vsync(mut self, vsync: bool) -> Self {
+        self.options.vsync = vsync;
+        self
+    }
+
+    pub fn canvas_options(mut self, options: CanvasOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn build<F: 'static + FnMut(CanvasCell, RefMut<Vec<Box<dyn Widget>>>)>(
+        &self,
+        video_subsystem: &VideoSubsystem,
+        update: F,
+    ) -> Result<MyWindow, String> {
+        let canvas = create_canvas(video_subsystem, self, self.options)?;
+        let mut window = MyWindow::new(update, canvas.id, canvas.canvas, true);
+        window.set_scale_factor(self.scale_factor);
+        window.set_parent(self.parent);
+        window.set_modal(self.modal);
+        window.set_persistent(self.persistent);
+        Ok(window)
+    }
+
+    pub(super) fn build_window(&self, video_subsystem: &VideoSubsystem, opengl: bool) -> Result<Window, String> {
+        let mut builder = video_subsystem.window(&self.title, self.width, self.height);
+        if opengl {
+            builder.opengl();
+        }
+        if self.resizable {
+            builder.resizable();
+        }
+        if self.borderless {
+            builder.borderless();
+        }
+        if self.hidden {
+            builder.hidden();
+        }
+        if self.high_dpi {
+            builder.allow_highdpi();
+        }
+        if self.always_on_top {
+            let flags = builder.window_flags() | SDL_WindowFlags::SDL_WINDOW_ALWAYS_ON_TOP as u32;
+            builder.set_window_flags(flags);
+        }
+        match self.position {
+            Position::Centered => builder.position_centered(),
+            Position::At(x, y) => builder.position(x, y),
+        };
+        let mut window = builder.build().map_err(|e| e.to_string())?;
+
+        if let Some((width, height)) = self.min_size {
+            window.set_minimum_size(width, height).map_err(|e| e.to_string())?;
+        }
+        if let Some((width, height)) = self.max_size {
+            window.set_maximum_size(width, height).map_err(|e| e.to_string())?;
+        }
+        if let Some(path) = &self.icon {
+            let icon = Surface::load_bmp(path)?;
+            window.set_icon(icon);
+        }
+        Ok(window)
+    }
+}
@@ -0,0 +1,56 @@
+use std::rc::Rc;
+
+use sdl2::{render::TextureCreator, video::WindowContext};
+
+use crate::widgets::Widget;
+
+use super::dispatch::visit;
+
+/// The window a widget is mounted in, passed to `Widget::on_mount`.
+pub struct WindowCtx {
+    id: u32,
+    texture_creator: Rc<TextureCreator<WindowContext>>,
+    scale_factor: f32,
+}
+
+impl WindowCtx {
+    pub fn new(id: u32, texture_creator: TextureCreator<WindowContext>, scale_factor: f32) -> Self {
+        Self {
+            id,
+            texture_creator: Rc::new(texture_creator),
+            scale_factor,
+        }
+    }
+
+    pub fn window_id(&self) -> u32 {
+        self.id
+    }
+
+    /// Creates textures for the renderer of the window. They are only valid for that renderer
+    /// and have to be destroyed in `Widget::on_unmount`, or be kept with the creator from
+    /// `shared_texture_creator`.
+    pub fn texture_creator(&self) -> &TextureCreator<WindowContext> {
+        &self.texture_creator
+    }
+
+    /// The texture creator to keep next to the textures made with it. It keeps the renderer
+    /// alive, so they can be destroyed as long as it is held.
+    pub fn shared_texture_creator(&self) -> Rc<TextureCreator<WindowContext>> {
+        self.texture_creator.clone()
+    }
+
+    /// Pixels per logical unit the window renders at when the widget is mounted, it can change
+    /// later with the display or the user scale factor.
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+}
+
+/// Calls `Widget::on_mount` on the widget and all of its descendants.
+pub fn mount(widget: &mut dyn Widget, ctx: &WindowCtx) {
+    visit(widget, &mut |w| w.on_mount(ctx));
+}
+
+pub fn unmount(widget: &mut dyn Widget) {
+    visit(widget, &mut |w| w.on_unmount());
+}
@@ -0,0 +1,337 @@
+use sdl2::{
+    event::{Event, WindowEvent},
+    keyboard::{Keycode, Mod},
+    rect::Point,
+};
+
+use crate::{widgets::Widget, Action};
+
+/// Index path of a widget in a window, the first index selects the root widget and every
+/// further one a child of the previous widget.
+pub type WidgetPath = Vec<usize>;
+
+/// Passed to `Widget::event` while an event travels through the widget tree. It describes the
+/// window rather than lending it out, its widgets are borrowed while they handle the event.
+#[derive(Clone, Copy)]
+pub struct EventCtx {
+    window_id: u32,
+    scale_factor: f32,
+    handled: bool,
+    target: bool,
+}
+
+impl EventCtx {
+    pub fn new(window_id: u32, scale_factor: f32) -> Self {
+        Self {
+            window_id,
+            scale_factor,
+            handled: false,
+            target: true,
+        }
+    }
+
+    pub fn window_id(&self) -> u32 {
+        self.window_id
+    }
+
+    /// Pixels per logical unit of the window, see `MyWindow::scale_factor`.
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    /// Stops the event from bubbling up to the ancestors of the widget.
+    pub fn set_handled(&mut self) {
+        self.handled = true;
+    }
+
+    pub fn is_handled(&self) -> bool {
+        self.handled
+    }
+
+    /// Whether the event was routed to this widget, as opposed to bubbling up from a child.
+    pub fn is_target(&self) -> bool {
+        self.target
+    }
+}
+
+/// Routes events through the widget tree of a window.
+/// Pointer events go to the topmost widget under the pointer, later siblings being on top of
+/// earlier ones, and bubble up to its ancestors until one marks them handled. A button press
+/// captures the pointer, so motion and the release reach the pressed widget even outside of it.
+/// Keyboard events go to the focused widget and bubble the same way, all other events are
+/// delivered to every widget. Clicking focuses the innermost focusable widget under the pointer,
+/// Tab and Shift+Tab move the focus through the focusable widgets in tree order.
+#[derive(Default)]
+pub struct Dispatcher {
+    hovered: WidgetPath,
+    captured: Option<WidgetPath>,
+    focused: Option<WidgetPath>,
+}
+
+impl Dispatcher {
+    pub fn dispatch(&mut self, widgets: &mut [Box<dyn Widget>], event: Event, ctx: EventCtx) -> Vec<Action> {
+        match event {
+            Event::MouseMotion { x, y, .. } => {
+                let hit = hit_path(widgets, Point::new(x, y));
+                self.set_hovered(widgets, hit.clone());
+                let target = self.captured.clone().unwrap_or(hit);
+                bubble(widgets, &target, event, ctx)
+            }
+            Event::MouseButtonDown { x, y, .. } => {
+                let hit = hit_path(widgets, Point::new(x, y));
+                self.captured = Some(hit.clone());
+                let focus = (1..=hit.len())
+                    .rev()
+                    .map(|depth| hit[..depth].to_vec())
+                    .find(|path| widget_at(widgets, path).is_some_and(|w| w.focusable()));
+                self.set_focus(widgets, focus);
+                bubble(widgets, &hit, event, ctx)
+            }
+            Event::MouseButtonUp { x, y, .. } => {
+                let target = match self.captured.take() {
+                    Some(path) => path,
+                    None => hit_path(widgets, Point::new(x, y)),
+                };
+                bubble(widgets, &target, event, ctx)
+            }
+            // wheel events carry no position, they go to the widget under the pointer
+            Event::MouseWheel { .. } => {
+                let target = self.hovered.clone();
+                bubble(widgets, &target, event, ctx)
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::Tab),
+                keymod,
+                ..
+            } => {
+                self.move_focus(widgets, !keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD));
+                vec![]
+            }
+            Event::KeyDown { .. } | Event::KeyUp { .. } | Event::TextInput { .. } | Event::TextEditing { .. } => {
+                match self.focused.clone() {
+                    Some(target) => bubble(widgets, &target, event, ctx),
+                    None => vec![],
+                }
+            }
+            Event::Window {
+                win_event: WindowEvent::Leave,
+                ..
+            } => {
+                self.set_hovered(widgets, vec![]);
+                broadcast(widgets, event, ctx)
+            }
+            _ => broadcast(widgets, event, ctx),
+        }
+    }
+
+    /// Forgets `removed`, the widget that was at `path` until it was taken out of the tree, and
+    /// its descendants. Hovered and focused ones among them are told they lost the pointer or
+    /// the focus.
+    pub fn release(&mut self, removed: &mut Box<dyn Widget>, path: &[usize]) {
+        let detached = std::slice::from_mut(removed);
+        // a path below `path` as seen from `detached`
+        let relative = |p: &[usize]| [&[0], &p[path.len()..]].concat();
+        if self.focused.as_ref().is_some_and(|f| f.starts_with(path)) {
+            let focused = self.focused.take().unwrap_or_default();
+            if let Some(widget) = widget_at_mut(detached, &relative(&focused)) {
+                widget.on_blur();
+            }
+        }
+        if !path.is_empty() && self.hovered.starts_with(path) {
+            for depth in (path.len()..=self.hovered.len()).rev() {
+                if let Some(widget) = widget_at_mut(detached, &relative(&self.hovered[..depth])) {
+                    widget.on_hover(false);
+                }
+            }
+            self.hovered.truncate(path.len() - 1);
+        }
+        if self.captured.as_ref().is_some_and(|c| c.starts_with(path)) {
+            self.captured = None;
+        }
+    }
+
+    /// Renumbers the paths through the children of `parent` after they were inserted, removed
+    /// or moved, `index` maps the old index of a child to its new one.
+    pub fn reindex<F: Fn(usize) -> usize>(&mut self, parent: &[usize], index: F) {
+        let depth = parent.len();
+        let paths = self
+            .focused
+            .iter_mut()
+            .chain(self.captured.iter_mut())
+            .chain(std::iter::once(&mut self.hovered));
+        for path in paths {
+            if path.len() > depth && path.starts_with(parent) {
+                path[depth] = index(path[depth]);
+            }
+        }
+    }
+
+    /// Notifies the widgets the pointer left and entered, the ancestors of the widget under the
+    /// pointer count as hovered too.
+    fn set_hovered(&mut self, widgets: &mut [Box<dyn Widget>], hovered: WidgetPath) {
+        let common = self.hovered.iter().zip(&hovered).take_while(|(a, b)| a == b).count();
+        for depth in (common + 1..=self.hovered.len()).rev() {
+            if let Some(widget) = widget_at_mut(widgets, &self.hovered[..depth]) {
+                widget.on_hover(false);
+            }
+        }
+        for depth in common + 1..=hovered.len() {
+            if let Some(widget) = widget_at_mut(widgets, &hovered[..depth]) {
+                widget.on_hover(true);
+            }
+        }
+        self.hovered = hovered;
+    }
+
+    fn set_focus(&mut self, widgets: &mut [Box<dyn Widget>], focused: Option<WidgetPath>) {
+        if focused == self.focused {
+            return;
+        }
+        if let Some(widget) = self.focused.as_ref().and_then(|path| widget_at_mut(widgets, path)) {
+            widget.on_blur();
+        }
+        if let Some(widget) = focused.as_ref().and_then(|path| widget_at_mut(widgets, path)) {
+            widget.on_focus();
+        }
+        self.focused = focused;
+    }
+
+    /// Focuses the next focusable widget in tree order, or the previous one, wrapping around.
+    fn move_focus(&mut self, widgets: &mut [Box<dyn Widget>], forward: bool) {
+        let mut paths = vec![];
+        for (index, widget) in widgets.iter().enumerate() {
+            collect_focusable(widget.as_ref(), &mut vec![index], &mut paths);
+        }
+        if paths.is_empty() {
+            return;
+        }
+        let len = paths.len();
+        let next = match self.focused.as_ref().and_then(|f| paths.iter().position(|p| p == f)) {
+            Some(i) if forward => (i + 1) % len,
+            Some(i) => (i + len - 1) % len,
+            None if forward => 0,
+            None => len - 1,
+        };
+        self.set_focus(widgets, Some(paths.swap_remove(next)));
+    }
+}
+
+/// Path of the topmost widget under `point`, in logical window coordinates.
+/// Children are only hit inside of their parent.
+pub fn hit_path(widgets: &[Box<dyn Widget>], point: Point) -> WidgetPath {
+    let mut path = vec![];
+    let root = match widgets.iter().rposition(|w| w.hit_test(point)) {
+        Some(root) => root,
+        None => return path,
+    };
+    path.push(root);
+    let mut widget: &dyn Widget = widgets[root].as_ref();
+    while let Some((index, child)) = (0..widget.child_count())
+        .rev()
+        .filter_map(|i| widget.child(i).map(|c| (i, c)))
+        .find(|(_, c)| c.hit_test(point))
+    {
+        path.push(index);
+        widget = child;
+    }
+    path
+}
+
+pub fn widget_at<'w>(widgets: &'w [Box<dyn Widget>], path: &[usize]) -> Option<&'w dyn Widget> {
+    let (first, rest) = path.split_first()?;
+    let mut widget: &dyn Widget = widgets.get(*first)?.as_ref();
+    for &index in rest {
+        widget = widget.child(index)?;
+    }
+    Some(widget)
+}
+
+pub fn widget_at_mut<'w>(widgets: &'w mut [Box<dyn Widget>], path: &[usize]) -> Option<&'w mut (dyn Widget + 'static)> {
+    let (first, rest) = path.split_first()?;
+    let mut widget: &mut (dyn Widget + 'static) = widgets.get_mut(*first)?.as_mut();
+    for &index in rest {
+        widget = widget.child_mut(index)?;
+    }
+    Some(widget)
+}
+
+/// Path of the first widget with the given id, parents before their children.
+pub fn find_path(widgets: &[Box<dyn Widget>], id: &str) -> Option<WidgetPath> {
+    for (index, widget) in widgets.iter().enumerate() {
+        let mut path = vec![index];
+        if find_in(widget.as_ref(), id, &mut path) {
+            return Some(path);
+        }
+    }
+    None
+}
+
+fn find_in(widget: &dyn Widget, id: &str, path: &mut WidgetPath) -> bool {
+    if widget.get_id() == Some(id) {
+        return true;
+    }
+    for index in 0..widget.child_count() {
+        if let Some(child) = widget.child(index) {
+            path.push(index);
+            if find_in(child, id, path) {
+                return true;
+            }
+            path.pop();
+        }
+    }
+    false
+}
+
+/// Calls `f` on every widget of the tree, parents before their children.
+pub fn visit(widget: &mut dyn Widget, f: &mut dyn FnMut(&mut dyn Widget)) {
+    f(widget);
+    for index in 0..widget.child_count() {
+        if let Some(child) = widget.child_mut(index) {
+            visit(child, f);
+        }
+    }
+}
+
+fn collect_focusable(widget: &dyn Widget, path: &mut WidgetPath, paths: &mut Vec<WidgetPath>) {
+    if widget.focusable() {
+        paths.push(path.clone());
+    }
+    for index in 0..widget.child_count() {
+        if let Some(child) = widget.child(index) {
+            path.push(index);
+            collect_focusable(child, path, paths);
+            path.pop();
+        }
+    }
+}
+
+/// Delivers the event to the widget at `path`, then to its ancestors until it is handled.
+fn bubble(widgets: &mut [Box<dyn Widget>], path: &[usize], event: Event, mut ctx: EventCtx) -> Vec<Action> {
+    let mut actions = vec![];
+    for depth in (1..=path.len()).rev() {
+        if let Some(widget) = widget_at_mut(widgets, &path[..depth]) {
+            match widget.event(event.clone(), &mut ctx) {
+                Action::None => {}
+                action => actions.push(action),
+            }
+        }
+        if ctx.handled {
+            break;
+        }
+        ctx.target = false;
+    }
+    actions
+}
+
+fn broadcast(widgets: &mut [Box<dyn Widget>], event: Event, ctx: EventCtx) -> Vec<Action> {
+    let mut actions = vec![];
+    for widget in widgets.iter_mut() {
+        visit(widget.as_mut(), &mut |w| {
+            match w.event(event.clone(), &mut ctx.clone()) {
+                Action::None => {}
+                action => actions.push(action),
+            }
+        });
+    }
+    actions
+}
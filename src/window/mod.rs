@@ -1,18 +1,119 @@
 use std::{
-    cell::{RefCell, RefMut},
+    cell::{Ref, RefCell, RefMut},
+    env,
     rc::Rc,
 };
 
-use sdl2::{event::Event, render::Canvas, video::Window, VideoSubsystem};
+use sdl2::{
+    event::{Event, WindowEvent},
+    pixels::PixelFormatEnum,
+    rect::Rect,
+    render::Canvas,
+    surface::Surface,
+    video::{FullscreenType, Window},
+    Sdl, VideoSubsystem,
+};
+
+use crate::{widgets::{move_item, Widget}, CanvasCell, DrawFn, Action};
+
+pub mod builder;
+pub mod context;
+pub mod dispatch;
+pub mod registry;
+pub mod session;
+
+use builder::WindowBuilder;
+use context::{mount, unmount, WindowCtx};
+use dispatch::{find_path, widget_at_mut, Dispatcher, EventCtx, WidgetPath};
+use session::WindowGeometry;
+
+/// Environment variable used to force a render driver ("auto", "gpu" or "software").
+pub const RENDERER_ENV: &str = "SDL_MULTIWINDOW_RENDERER";
+/// Environment variable that enables vsync for new canvases when set to "1" or "true".
+pub const VSYNC_ENV: &str = "SDL_MULTIWINDOW_VSYNC";
+/// Environment variable that switches SDL to the dummy video driver when set to "1" or "true".
+pub const HEADLESS_ENV: &str = "SDL_MULTIWINDOW_HEADLESS";
+
+/// Render driver selection policy for a window's canvas.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Renderer {
+    /// Prefer the "opengl" driver and fall back to SDL's software driver.
+    Auto,
+    /// Only use the "opengl" driver.
+    Gpu,
+    /// Only use SDL's software driver.
+    Software,
+}
+
+impl Renderer {
+    /// Reads the policy from `SDL_MULTIWINDOW_RENDERER`, defaulting to `Auto`.
+    /// Headless mode always renders in software since the dummy driver has no GL.
+    pub fn from_env() -> Self {
+        if is_headless() {
+            return Renderer::Software;
+        }
+        match env::var(RENDERER_ENV).map(|v| v.to_lowercase()).as_deref() {
+            Ok("gpu") | Ok("opengl") => Renderer::Gpu,
+            Ok("software") => Renderer::Software,
+            _ => Renderer::Auto,
+        }
+    }
+}
+
+/// How root widgets are sized when the window lays them out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LayoutPolicy {
+    /// Root widgets keep the rect they were created with.
+    Absolute,
+    /// Root widgets are stretched to cover the whole window.
+    Fill,
+}
+
+/// Options used when creating a window's canvas.
+#[derive(Clone, Copy, Debug)]
+pub struct CanvasOptions {
+    pub renderer: Renderer,
+    /// Synchronizes `present` with the display refresh rate.
+    pub vsync: bool,
+}
+
+impl Default for CanvasOptions {
+    fn default() -> Self {
+        Self {
+            renderer: Renderer::from_env(),
+            vsync: matches!(env::var(VSYNC_ENV).as_deref(), Ok("1") | Ok("true")),
+        }
+    }
+}
+
+pub fn is_headless() -> bool {
+    matches!(env::var(HEADLESS_ENV).as_deref(), Ok("1") | Ok("true"))
+}
 
-use crate::{widgets::Widget, CanvasCell, DrawFn, Action};
+/// Initializes SDL, selecting the dummy video driver first when running headless.
+pub fn init_sdl() -> Result<Sdl, String> {
+    if is_headless() {
+        env::set_var("SDL_VIDEODRIVER", "dummy");
+    }
+    sdl2::init()
+}
 
 pub struct MyWindow {
     update: DrawFn,
     id: u32,
     active: bool,
+    parent: Option<u32>,
+    modal: bool,
+    blocked: bool,
+    persistent: bool,
+    dirty: bool,
+    needs_layout: bool,
+    layout_policy: LayoutPolicy,
+    ui_scale: f32,
+    dpi_scale: f32,
     canvas: CanvasCell,
     widgets: Rc<RefCell<Vec<Box<dyn Widget>>>>,
+    dispatcher: Dispatcher,
 }
 
 impl MyWindow {
@@ -26,8 +127,18 @@ impl MyWindow {
             update: Box::new(update),
             id,
             active,
+            parent: None,
+            modal: false,
+            blocked: false,
+            persistent: false,
+            dirty: true,
+            needs_layout: true,
+            layout_policy: LayoutPolicy::Absolute,
+            ui_scale: 1.,
+            dpi_scale: 1.,
             canvas,
             widgets: Rc::new(RefCell::new(vec![])),
+            dispatcher: Dispatcher::default(),
         }
     }
 
@@ -47,20 +158,283 @@ impl MyWindow {
         self.active = active;
     }
 
-    pub fn event(&mut self, event: Event) -> Vec<Action> {
-        let mut actions = vec![];
+    /// Id of the window owning this one, owned windows close together with their parent.
+    pub fn parent(&self) -> Option<u32> {
+        self.parent
+    }
+
+    pub fn set_parent(&mut self, parent: Option<u32>) {
+        self.parent = parent;
+    }
+
+    /// A modal window blocks input to its parent while it is open.
+    pub fn is_modal(&self) -> bool {
+        self.modal
+    }
+
+    pub fn set_modal(&mut self, modal: bool) {
+        self.modal = modal;
+    }
+
+    /// Whether a modal child currently blocks input to this window.
+    pub fn is_blocked(&self) -> bool {
+        self.blocked
+    }
+
+    pub fn set_blocked(&mut self, blocked: bool) {
+        self.blocked = blocked;
+    }
+
+    /// Persistent windows have their geometry stored in the registry's session under their key.
+    pub fn is_persistent(&self) -> bool {
+        self.persistent
+    }
+
+    pub fn set_persistent(&mut self, persistent: bool) {
+        self.persistent = persistent;
+    }
+
+    pub fn geometry(&self) -> WindowGeometry {
+        let canvas = self.canvas.borrow();
+        let window = canvas.window();
+        let (x, y) = window.position();
+        let (width, height) = window.size();
+        WindowGeometry {
+            x,
+            y,
+            width,
+            height,
+            maximized: window.is_maximized(),
+            display: window.display_index().unwrap_or(0),
+        }
+    }
+
+    /// Moves and resizes the window to `geometry`, clamped to the available displays.
+    pub fn apply_geometry(&mut self, geometry: &WindowGeometry) -> Result<(), String> {
+        let mut canvas = self.canvas.borrow_mut();
+        let window = canvas.window_mut();
+        let geometry = geometry.clamp_to_displays(window.subsystem());
+        window.set_size(geometry.width, geometry.height).map_err(|e| e.to_string())?;
+        window.set_position(geometry.x.into(), geometry.y.into());
+        if geometry.maximized {
+            window.maximize();
+        }
+        Ok(())
+    }
+
+    /// Moves the window so it is centered on `parent`.
+    pub fn center_on(&mut self, parent: &MyWindow) {
+        let (px, py) = parent.position();
+        let (pw, ph) = parent.size();
+        let (w, h) = self.size();
+        self.set_position(px + (pw as i32 - w as i32) / 2, py + (ph as i32 - h as i32) / 2);
+    }
+
+    /// Brings the window to the front and gives it input focus, restoring it if minimized.
+    pub fn raise(&mut self) {
+        let mut canvas = self.canvas.borrow_mut();
+        let window = canvas.window_mut();
+        if window.is_minimized() {
+            window.restore();
+        }
+        window.raise();
+    }
+
+    pub fn position(&self) -> (i32, i32) {
+        self.canvas.borrow().window().position()
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        self.canvas.borrow().window().size()
+    }
+
+    pub fn show(&mut self) {
+        self.canvas.borrow_mut().window_mut().show();
+    }
+
+    pub fn hide(&mut self) {
+        self.canvas.borrow_mut().window_mut().hide();
+    }
+
+    pub fn minimize(&mut self) {
+        self.canvas.borrow_mut().window_mut().minimize();
+    }
+
+    pub fn maximize(&mut self) {
+        self.canvas.borrow_mut().window_mut().maximize();
+    }
+
+    pub fn restore(&mut self) {
+        self.canvas.borrow_mut().window_mut().restore();
+    }
+
+    pub fn set_title(&mut self, title: &str) -> Result<(), String> {
+        self.canvas.borrow_mut().window_mut().set_title(title).map_err(|e| e.to_string())
+    }
+
+    pub fn set_size(&mut self, width: u32, height: u32) -> Result<(), String> {
+        self.canvas.borrow_mut().window_mut().set_size(width, height).map_err(|e| e.to_string())
+    }
+
+    pub fn set_position(&mut self, x: i32, y: i32) {
+        self.canvas.borrow_mut().window_mut().set_position(x.into(), y.into());
+    }
+
+    /// Switches between windowed and desktop fullscreen mode.
+    pub fn toggle_fullscreen(&mut self) -> Result<(), String> {
+        let mut canvas = self.canvas.borrow_mut();
+        let window = canvas.window_mut();
+        let state = match window.fullscreen_state() {
+            FullscreenType::Off => FullscreenType::Desktop,
+            _ => FullscreenType::Off,
+        };
+        window.set_fullscreen(state)
+    }
+
+    pub fn set_layout_policy(&mut self, policy: LayoutPolicy) {
+        self.layout_policy = policy;
+        self.needs_layout = true;
+    }
+
+    /// Sets the user scale factor, widgets are laid out in logical units of `scale` window points.
+    pub fn set_scale_factor(&mut self, scale: f32) {
+        if scale > 0. && scale != self.ui_scale {
+            self.ui_scale = scale;
+            self.needs_layout = true;
+        }
+    }
+
+    /// Pixels per logical unit, the product of the display density and the user scale factor.
+    pub fn scale_factor(&self) -> f32 {
+        self.dpi_scale * self.ui_scale
+    }
+
+    /// Window size in the logical units widgets are laid out in.
+    pub fn logical_size(&self) -> (u32, u32) {
+        let (width, height) = self.size();
+        (
+            (width as f32 / self.ui_scale) as u32,
+            (height as f32 / self.ui_scale) as u32,
+        )
+    }
+
+    /// Runs the layout pass over the root widgets using the current window size and scale.
+    pub fn relayout(&mut self) {
+        let (width, _) = self.size();
+        {
+            let mut canvas = self.canvas.borrow_mut();
+            // the renderer output is in pixels, the window size in points on high-DPI displays
+            if let Ok((output_width, _)) = canvas.output_size() {
+                if width > 0 {
+                    self.dpi_scale = output_width as f32 / width as f32;
+                }
+            }
+            let scale = self.dpi_scale * self.ui_scale;
+            if let Err(e) = canvas.set_scale(scale, scale) {
+                eprintln!("Could not set render scale: {}", e);
+            }
+        }
+        let (width, height) = self.logical_size();
+        let bounds = Rect::new(0, 0, width, height);
         for widget in (*self.widgets).borrow_mut().iter_mut() {
-            let action = widget.event(event.clone(), self);
-            match action {
-                Action::None => {},
-                _ => actions.push(action)
+            if self.layout_policy == LayoutPolicy::Fill {
+                widget.set_rect(bounds);
             }
+            widget.layout(bounds);
         }
-        actions
+        self.needs_layout = false;
+        self.dirty = true;
     }
 
+    /// Forces a repaint on the next frame.
+    pub fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+
+    pub fn needs_redraw(&self) -> bool {
+        self.dirty || (*self.widgets).borrow().iter().any(|w| w.is_dirty())
+    }
+
+    pub fn event(&mut self, event: Event) -> Vec<Action> {
+        if self.blocked && is_input(&event) {
+            return vec![];
+        }
+        if let Event::Window { win_event, .. } = &event {
+            match win_event {
+                WindowEvent::Resized(..)
+                | WindowEvent::SizeChanged(..)
+                | WindowEvent::DisplayChanged(..) => self.needs_layout = true,
+                WindowEvent::Shown
+                | WindowEvent::Exposed
+                | WindowEvent::Maximized
+                | WindowEvent::Restored => self.invalidate(),
+                _ => {}
+            }
+        }
+        let event = self.to_logical(event);
+        let ctx = EventCtx::new(self.get_id(), self.scale_factor());
+        self.dispatcher.dispatch(&mut self.widgets.borrow_mut(), event, ctx)
+    }
+
+    /// Converts mouse coordinates from window points to logical units.
+    fn to_logical(&self, event: Event) -> Event {
+        if self.ui_scale == 1. {
+            return event;
+        }
+        let scale = |v: i32| (v as f32 / self.ui_scale) as i32;
+        match event {
+            Event::MouseMotion { timestamp, window_id, which, mousestate, x, y, xrel, yrel } => Event::MouseMotion {
+                timestamp,
+                window_id,
+                which,
+                mousestate,
+                x: scale(x),
+                y: scale(y),
+                xrel: scale(xrel),
+                yrel: scale(yrel),
+            },
+            Event::MouseButtonDown { timestamp, window_id, which, mouse_btn, clicks, x, y } => Event::MouseButtonDown {
+                timestamp,
+                window_id,
+                which,
+                mouse_btn,
+                clicks,
+                x: scale(x),
+                y: scale(y),
+            },
+            Event::MouseButtonUp { timestamp, window_id, which, mouse_btn, clicks, x, y } => Event::MouseButtonUp {
+                timestamp,
+                window_id,
+                which,
+                mouse_btn,
+                clicks,
+                x: scale(x),
+                y: scale(y),
+            },
+            event => event,
+        }
+    }
+
+    /// Advances time based widget state, `dt` is in seconds.
+    pub fn tick(&mut self, dt: f32) {
+        for widget in (*self.widgets).borrow_mut().iter_mut() {
+            widget.tick(dt);
+        }
+    }
+
+    /// Repaints the window if it was invalidated or one of its widgets is dirty.
     pub fn update(&mut self) {
+        if self.needs_layout {
+            self.relayout();
+        }
+        if self.needs_redraw() {
+            self.redraw();
+        }
+    }
+
+    pub fn redraw(&mut self) {
         (self.update)(self.canvas.clone(), (*self.widgets).borrow_mut());
+        self.dirty = false;
     }
 
     pub fn create<F: 'static + FnMut(CanvasCell, RefMut<Vec<Box<dyn Widget>>>)>(
@@ -69,13 +443,223 @@ impl MyWindow {
         width: u32,
         height: u32,
         update: F,
-    ) -> Self {
-        let canvas = to_canvas(add_window(video_subsystem, title, width, height).unwrap()).unwrap();
-        MyWindow::new(update, canvas.id, canvas.canvas, true)
+    ) -> Result<Self, String> {
+        WindowBuilder::new(title, width, height).build(video_subsystem, update)
+    }
+
+    /// Lays out and draws the window, then reads the rendered frame back into a surface.
+    /// Only reliable with the software renderer, GPU renderers may drop the frame on `present`.
+    pub fn snapshot(&mut self) -> Result<Surface<'static>, String> {
+        if self.needs_layout {
+            self.relayout();
+        }
+        self.redraw();
+
+        let canvas = self.canvas.borrow();
+        let (width, height) = canvas.output_size()?;
+        let format = PixelFormatEnum::ARGB8888;
+        let pixels = canvas.read_pixels(None, format)?;
+        let mut surface = Surface::new(width, height, format)?;
+        let pitch = surface.pitch() as usize;
+        let row = width as usize * format.byte_size_per_pixel();
+        surface.with_lock_mut(|data| {
+            for (y, src) in pixels.chunks(row).enumerate() {
+                data[y * pitch..y * pitch + row].copy_from_slice(src);
+            }
+        });
+        Ok(surface)
     }
 
-    pub fn add_widget(&mut self, widget: Box<dyn Widget>) {
+    /// The widget with the given id anywhere in the tree, if it is a `T`.
+    /// The widgets stay borrowed until the returned reference is dropped. While they are
+    /// borrowed elsewhere, e.g. when called from a widget callback, nothing is found.
+    pub fn find<T: Widget + 'static>(&self, id: &str) -> Option<RefMut<'_, T>> {
+        RefMut::filter_map(self.find_widget(id)?, |w| w.as_any_mut().downcast_mut::<T>()).ok()
+    }
+
+    /// Like `find`, without knowing the type of the widget.
+    pub fn find_widget(&self, id: &str) -> Option<RefMut<'_, dyn Widget>> {
+        let widgets = (*self.widgets).try_borrow_mut().ok()?;
+        let path = find_path(&widgets, id)?;
+        RefMut::filter_map(widgets, |w| widget_at_mut(w, &path).map(|w| w as &mut dyn Widget)).ok()
+    }
+
+    /// The root widgets, e.g. to inspect their state in tests.
+    pub fn widgets(&self) -> Ref<'_, Vec<Box<dyn Widget>>> {
+        (*self.widgets).borrow()
+    }
+
+    pub fn add_widget(&mut self, mut widget: Box<dyn Widget>) {
+        mount(widget.as_mut(), &self.ctx());
         (*self.widgets).borrow_mut().push(widget);
+        self.needs_layout = true;
+    }
+
+    /// Context handed to widgets when they are mounted in this window.
+    pub fn ctx(&self) -> WindowCtx {
+        WindowCtx::new(self.id, self.canvas.borrow().texture_creator(), self.scale_factor())
+    }
+
+    /// Inserts a root widget before `index`, or last if `index` is past the end.
+    pub fn insert_widget(&mut self, index: usize, widget: Box<dyn Widget>) {
+        let _ = self.insert_at(&[], index, widget);
+    }
+
+    /// Inserts a child into the container with the id `parent`.
+    pub fn insert_child(&mut self, parent: &str, index: usize, widget: Box<dyn Widget>) -> Result<(), String> {
+        let path = self.path_of(parent)?;
+        self.insert_at(&path, index, widget)
+    }
+
+    pub fn remove_widget(&mut self, index: usize) -> Option<Box<dyn Widget>> {
+        self.remove_at(&[index])
+    }
+
+    /// Removes the widget with the given id from its parent, wherever it is in the tree.
+    pub fn remove_widget_by_id(&mut self, id: &str) -> Option<Box<dyn Widget>> {
+        let path = self.path_of(id).ok()?;
+        self.remove_at(&path)
+    }
+
+    /// Puts `widget` in the place of the widget with the given id and returns the old one.
+    pub fn replace_widget(&mut self, id: &str, widget: Box<dyn Widget>) -> Result<Box<dyn Widget>, String> {
+        let path = self.path_of(id)?;
+        let (index, parent) = path.split_last().unwrap();
+        let ctx = self.ctx();
+        let mut old = {
+            let mut widgets = (*self.widgets).borrow_mut();
+            let mut old = if parent.is_empty() {
+                std::mem::replace(&mut widgets[*index], widget)
+            } else {
+                widget_at_mut(&mut widgets, parent)
+                    .and_then(|p| p.replace_child(*index, widget))
+                    .ok_or(format!("cannot replace widget \"{}\"", id))?
+            };
+            self.dispatcher.release(&mut old, &path);
+            if let Some(new) = widget_at_mut(&mut widgets, &path) {
+                mount(new, &ctx);
+            }
+            old
+        };
+        unmount(old.as_mut());
+        self.tree_changed();
+        Ok(old)
+    }
+
+    /// Moves a root widget, which changes the order they are laid out and drawn in.
+    pub fn move_widget(&mut self, from: usize, to: usize) -> Result<(), String> {
+        self.move_at(&[], from, to)
+    }
+
+    /// Moves a child of the container with the id `parent`.
+    pub fn move_child(&mut self, parent: &str, from: usize, to: usize) -> Result<(), String> {
+        let path = self.path_of(parent)?;
+        self.move_at(&path, from, to)
+    }
+
+    pub fn clear_widgets(&mut self) {
+        while self.remove_at(&[0]).is_some() {}
+    }
+
+    /// Removes all children of the container with the id `parent`.
+    pub fn clear_children(&mut self, parent: &str) -> Result<(), String> {
+        let mut path = self.path_of(parent)?;
+        path.push(0);
+        while self.remove_at(&path).is_some() {}
+        Ok(())
+    }
+
+    fn path_of(&self, id: &str) -> Result<WidgetPath, String> {
+        find_path(&(*self.widgets).borrow(), id).ok_or(format!("no widget \"{}\"", id))
+    }
+
+    /// Inserts into the root widgets if `parent` is empty, into the widget at `parent` otherwise.
+    fn insert_at(&mut self, parent: &[usize], index: usize, widget: Box<dyn Widget>) -> Result<(), String> {
+        let ctx = self.ctx();
+        {
+            let mut widgets = (*self.widgets).borrow_mut();
+            let index = if parent.is_empty() {
+                let index = index.min(widgets.len());
+                widgets.insert(index, widget);
+                index
+            } else {
+                let container = widget_at_mut(&mut widgets, parent).ok_or("no such container")?;
+                let index = index.min(container.child_count());
+                container.insert_child(index, widget)?;
+                index
+            };
+            self.dispatcher.reindex(parent, |i| if i >= index { i + 1 } else { i });
+            let path = [parent, &[index]].concat();
+            if let Some(widget) = widget_at_mut(&mut widgets, &path) {
+                mount(widget, &ctx);
+            }
+        }
+        self.tree_changed();
+        Ok(())
+    }
+
+    fn remove_at(&mut self, path: &[usize]) -> Option<Box<dyn Widget>> {
+        let (index, parent) = path.split_last()?;
+        let mut removed = {
+            let mut widgets = (*self.widgets).borrow_mut();
+            // containers like `ScrollView` refuse to give up their children
+            let mut removed = if parent.is_empty() {
+                (*index < widgets.len()).then(|| widgets.remove(*index))
+            } else {
+                widget_at_mut(&mut widgets, parent).and_then(|p| p.remove_child(*index))
+            }?;
+            self.dispatcher.release(&mut removed, path);
+            self.dispatcher.reindex(parent, |i| if i > *index { i - 1 } else { i });
+            removed
+        };
+        self.tree_changed();
+        unmount(removed.as_mut());
+        Some(removed)
+    }
+
+    fn move_at(&mut self, parent: &[usize], from: usize, to: usize) -> Result<(), String> {
+        {
+            let mut widgets = (*self.widgets).borrow_mut();
+            if parent.is_empty() {
+                move_item(&mut widgets, from, to)?;
+            } else {
+                widget_at_mut(&mut widgets, parent)
+                    .ok_or("no such container")?
+                    .move_child(from, to)?;
+            }
+            self.dispatcher.reindex(parent, |i| {
+                // same as removing the child at `from` and inserting it again at `to`
+                let i = match i {
+                    i if i == from => return to,
+                    i if i > from => i - 1,
+                    i => i,
+                };
+                if i >= to {
+                    i + 1
+                } else {
+                    i
+                }
+            });
+        }
+        self.tree_changed();
+        Ok(())
+    }
+
+    /// Lays the window out again and repaints it after widgets were added, removed or moved.
+    fn tree_changed(&mut self) {
+        self.needs_layout = true;
+        self.dirty = true;
+    }
+}
+
+impl Drop for MyWindow {
+    /// Unmounts the widgets, so their textures and the renderer they keep alive are freed.
+    fn drop(&mut self) {
+        if let Ok(mut widgets) = (*self.widgets).try_borrow_mut() {
+            for widget in widgets.iter_mut() {
+                unmount(widget.as_mut());
+            }
+        }
     }
 }
 
@@ -90,33 +674,70 @@ impl MyCanvas {
     }
 }
 
-fn add_window(
-    video_subsystem: &VideoSubsystem,
-    title: &str,
-    width: u32,
-    height: u32,
-) -> Result<Window, String> {
-    let window = video_subsystem
-        .window(title, width, height)
-        .opengl()
-        .position_centered()
-        .build()
-        .map_err(|e| e.to_string())?;
-    Ok(window)
-}
-
-fn find_sdl_gl_driver() -> Option<u32> {
+/// Events coming from the user rather than from the window system.
+pub fn is_input(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::MouseMotion { .. }
+            | Event::MouseButtonDown { .. }
+            | Event::MouseButtonUp { .. }
+            | Event::MouseWheel { .. }
+            | Event::KeyDown { .. }
+            | Event::KeyUp { .. }
+            | Event::TextInput { .. }
+            | Event::TextEditing { .. }
+    )
+}
+
+fn find_sdl_driver(name: &str) -> Option<u32> {
     for (index, item) in sdl2::render::drivers().enumerate() {
-        if item.name == "opengl" {
+        if item.name == name {
             return Some(index as u32);
         }
     }
     None
 }
 
-fn to_canvas(window: Window) -> Result<MyCanvas, String> {
+fn create_canvas(
+    video_subsystem: &VideoSubsystem,
+    builder: &WindowBuilder,
+    options: CanvasOptions,
+) -> Result<MyCanvas, String> {
+    match options.renderer {
+        Renderer::Gpu => {
+            let window = builder.build_window(video_subsystem, true)?;
+            to_canvas(window, options)
+        }
+        Renderer::Software => {
+            let window = builder.build_window(video_subsystem, false)?;
+            to_canvas(window, options)
+        }
+        Renderer::Auto => {
+            // the window is consumed by a failed canvas build, so the fallback needs a fresh one
+            if find_sdl_driver("opengl").is_some() {
+                let gpu = CanvasOptions { renderer: Renderer::Gpu, ..options };
+                match create_canvas(video_subsystem, builder, gpu) {
+                    Ok(canvas) => return Ok(canvas),
+                    Err(e) => eprintln!("GPU renderer unavailable ({}), falling back to software", e),
+                }
+            }
+            let software = CanvasOptions { renderer: Renderer::Software, ..options };
+            create_canvas(video_subsystem, builder, software)
+        }
+    }
+}
+
+fn to_canvas(window: Window, options: CanvasOptions) -> Result<MyCanvas, String> {
     let id = window.id();
-    let canvas = window.into_canvas().index(find_sdl_gl_driver().unwrap()).build().map_err(|e| e.to_string())?;
+    let mut builder = window.into_canvas();
+    if options.vsync {
+        builder = builder.present_vsync();
+    }
+    let builder = match options.renderer {
+        Renderer::Software => builder.software(),
+        _ => builder.index(find_sdl_driver("opengl").ok_or("no \"opengl\" render driver available")?),
+    };
+    let canvas = builder.build().map_err(|e| e.to_string())?;
     let canvas_cell = Rc::new(RefCell::new(canvas));
     Ok(MyCanvas::new(id, canvas_cell))
 }
@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+
+use crate::WindowFactory;
+
+use super::{session::Session, MyWindow};
+
+/// Refers to a window from an `Action`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WindowRef {
+    /// The window whose widget returned the action.
+    Current,
+    Id(u32),
+    Key(String),
+}
+
+impl From<u32> for WindowRef {
+    fn from(id: u32) -> Self {
+        WindowRef::Id(id)
+    }
+}
+
+impl From<&str> for WindowRef {
+    fn from(key: &str) -> Self {
+        WindowRef::Key(key.to_string())
+    }
+}
+
+/// Keeps every open window, addressable by SDL window id and optionally by a logical key.
+/// Windows keep their insertion order, the first one is treated as the main window.
+/// Removing a window also removes the windows it owns, modal windows block their parent.
+#[derive(Default)]
+pub struct WindowRegistry {
+    windows: Vec<MyWindow>,
+    keys: HashMap<String, u32>,
+    session: Option<Session>,
+}
+
+impl WindowRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables restoring and saving the geometry of persistent windows. Keyed windows that are
+    /// already registered get their stored geometry back right away.
+    pub fn set_session(&mut self, session: Session) {
+        for (key, id) in &self.keys {
+            if let Some(window) = self.windows.iter_mut().find(|w| w.get_id() == *id) {
+                restore_geometry(&session, key, window);
+            }
+        }
+        self.session = Some(session);
+    }
+
+    pub fn session(&self) -> Option<&Session> {
+        self.session.as_ref()
+    }
+
+    pub fn insert(&mut self, mut window: MyWindow) -> u32 {
+        let id = window.get_id();
+        if window.is_modal() {
+            if let Some(parent) = window.parent().and_then(|p| self.get(p)) {
+                window.center_on(parent);
+            }
+        }
+        self.windows.push(window);
+        self.update_blocked();
+        id
+    }
+
+    /// Registers a window under `key`, replacing (and closing) any window previously using it.
+    /// Persistent windows get their stored geometry back.
+    pub fn insert_keyed(&mut self, key: &str, mut window: MyWindow) -> u32 {
+        self.remove_by_key(key);
+        if let Some(session) = self.session.as_ref() {
+            restore_geometry(session, key, &mut window);
+        }
+        let id = self.insert(window);
+        self.keys.insert(key.to_string(), id);
+        id
+    }
+
+    /// Raises and focuses the window registered under `key`, only calling `factory` if there is none.
+    pub fn create_if_not_exists(&mut self, key: &str, factory: WindowFactory) -> Result<u32, String> {
+        if let Some(window) = self.get_by_key_mut(key) {
+            window.raise();
+            return Ok(window.get_id());
+        }
+        let window = factory()?;
+        Ok(self.insert_keyed(key, window))
+    }
+
+    /// Resolves `target` to a window id, `source` is the window that produced the action.
+    pub fn resolve(&self, target: &WindowRef, source: u32) -> Option<u32> {
+        let id = match target {
+            WindowRef::Current => source,
+            WindowRef::Id(id) => *id,
+            WindowRef::Key(key) => self.id_of(key)?,
+        };
+        self.contains(id).then_some(id)
+    }
+
+    pub fn contains(&self, id: u32) -> bool {
+        self.windows.iter().any(|w| w.get_id() == id)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.keys.contains_key(key)
+    }
+
+    pub fn id_of(&self, key: &str) -> Option<u32> {
+        self.keys.get(key).copied()
+    }
+
+    pub fn key_of(&self, id: u32) -> Option<&str> {
+        self.keys.iter().find(|(_, v)| **v == id).map(|(k, _)| k.as_str())
+    }
+
+    pub fn get(&self, id: u32) -> Option<&MyWindow> {
+        self.windows.iter().find(|w| w.get_id() == id)
+    }
+
+    pub fn get_mut(&mut self, id: u32) -> Option<&mut MyWindow> {
+        self.windows.iter_mut().find(|w| w.get_id() == id)
+    }
+
+    pub fn get_by_key(&self, key: &str) -> Option<&MyWindow> {
+        self.get(self.id_of(key)?)
+    }
+
+    pub fn get_by_key_mut(&mut self, key: &str) -> Option<&mut MyWindow> {
+        self.get_mut(self.id_of(key)?)
+    }
+
+    pub fn main_window(&self) -> Option<&MyWindow> {
+        self.windows.first()
+    }
+
+    pub fn is_main_window(&self, id: u32) -> bool {
+        self.windows.first().is_some_and(|w| w.get_id() == id)
+    }
+
+    /// Removes the window together with every window it owns, directly or indirectly.
+    pub fn remove(&mut self, id: u32) -> Option<MyWindow> {
+        let window = self.remove_tree(id)?;
+        self.save_session();
+        self.update_blocked();
+        Some(window)
+    }
+
+    fn remove_tree(&mut self, id: u32) -> Option<MyWindow> {
+        let pos = self.windows.iter().position(|w| w.get_id() == id)?;
+        self.record_session(id, false);
+        self.keys.retain(|_, v| *v != id);
+        let window = self.windows.remove(pos);
+        for child in self.children_of(id) {
+            self.remove_tree(child);
+        }
+        Some(window)
+    }
+
+    pub fn children_of(&self, id: u32) -> Vec<u32> {
+        self.windows
+            .iter()
+            .filter(|w| w.parent() == Some(id))
+            .map(|w| w.get_id())
+            .collect()
+    }
+
+    /// The open modal window blocking `id`, if any.
+    pub fn modal_child_of(&self, id: u32) -> Option<u32> {
+        self.windows
+            .iter()
+            .find(|w| w.is_modal() && w.parent() == Some(id))
+            .map(|w| w.get_id())
+    }
+
+    /// Closes every window, remembering the persistent ones as open for the next start.
+    pub fn close_all(&mut self) {
+        let ids: Vec<u32> = self.windows.iter().map(|w| w.get_id()).collect();
+        for id in ids {
+            self.record_session(id, true);
+        }
+        self.save_session();
+        self.windows.clear();
+        self.keys.clear();
+    }
+
+    /// Stores the geometry of a persistent keyed window, `save_session` writes it out.
+    fn record_session(&mut self, id: u32, open: bool) {
+        let (Some(session), Some(window)) = (self.session.as_mut(), self.windows.iter().find(|w| w.get_id() == id)) else {
+            return;
+        };
+        if !window.is_persistent() {
+            return;
+        }
+        if let Some(key) = self.keys.iter().find(|(_, v)| **v == id).map(|(k, _)| k) {
+            session.record(key, window.geometry(), open);
+        }
+    }
+
+    fn save_session(&mut self) {
+        if let Some(session) = self.session.as_mut() {
+            if let Err(e) = session.save() {
+                eprintln!("Could not save session: {}", e);
+            }
+        }
+    }
+
+    fn update_blocked(&mut self) {
+        let blocked: Vec<u32> = self
+            .windows
+            .iter()
+            .filter(|w| w.is_modal())
+            .filter_map(|w| w.parent())
+            .collect();
+        for window in self.windows.iter_mut() {
+            window.set_blocked(blocked.contains(&window.get_id()));
+        }
+    }
+
+    pub fn remove_by_key(&mut self, key: &str) -> Option<MyWindow> {
+        let id = self.id_of(key)?;
+        self.remove(id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &MyWindow> {
+        self.windows.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut MyWindow> {
+        self.windows.iter_mut()
+    }
+
+    pub fn len(&self) -> usize {
+        self.windows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.windows.is_empty()
+    }
+}
+
+/// Applies the geometry stored for `key` if the window is persistent.
+fn restore_geometry(session: &Session, key: &str, window: &mut MyWindow) {
+    if !window.is_persistent() {
+        return;
+    }
+    if let Some(geometry) = session.geometry(key) {
+        if let Err(e) = window.apply_geometry(geometry) {
+            eprintln!("Could not restore window \"{}\": {}", key, e);
+        }
+    }
+}
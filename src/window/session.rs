@@ -0,0 +1,256 @@
+use std::{collections::HashMap, fs, io::ErrorKind, path::PathBuf};
+
+use sdl2::{rect::Rect, VideoSubsystem};
+
+/// Position and state of a window as stored in a session.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub maximized: bool,
+    pub display: i32,
+}
+
+impl WindowGeometry {
+    /// Moves and shrinks the geometry so it lies on one of the available displays,
+    /// falling back to the first display if the stored one is gone.
+    pub fn clamp_to_displays(&self, video_subsystem: &VideoSubsystem) -> Self {
+        let displays: Vec<Option<Rect>> = (0..video_subsystem.num_video_displays().unwrap_or(1))
+            .map(|display| video_subsystem.display_usable_bounds(display).ok())
+            .collect();
+        self.clamp_to(&displays)
+    }
+
+    /// `clamp_to_displays` with the usable bounds of every display, `None` where they are
+    /// unknown, which leaves the geometry as it is.
+    fn clamp_to(&self, displays: &[Option<Rect>]) -> Self {
+        let display = if self.display >= 0 && (self.display as usize) < displays.len() { self.display } else { 0 };
+        let bounds = match displays.get(display as usize).copied().flatten() {
+            Some(bounds) => bounds,
+            None => return self.clone(),
+        };
+        let width = self.width.min(bounds.width());
+        let height = self.height.min(bounds.height());
+        let x = self.x.clamp(bounds.x(), bounds.x() + (bounds.width() - width) as i32);
+        let y = self.y.clamp(bounds.y(), bounds.y() + (bounds.height() - height) as i32);
+        Self {
+            x,
+            y,
+            width,
+            height,
+            maximized: self.maximized,
+            display,
+        }
+    }
+}
+
+/// Where a session is read from and written to.
+pub trait SessionStorage {
+    /// Returns `None` if nothing was stored yet.
+    fn load(&self) -> Result<Option<String>, String>;
+    fn save(&self, data: &str) -> Result<(), String>;
+}
+
+/// Stores the session in a plain text file.
+pub struct FileStorage {
+    path: PathBuf,
+}
+
+impl FileStorage {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl SessionStorage for FileStorage {
+    fn load(&self) -> Result<Option<String>, String> {
+        match fs::read_to_string(&self.path) {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("{}: {}", self.path.display(), e)),
+        }
+    }
+
+    fn save(&self, data: &str) -> Result<(), String> {
+        if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        }
+        fs::write(&self.path, data).map_err(|e| format!("{}: {}", self.path.display(), e))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct SessionEntry {
+    geometry: WindowGeometry,
+    open: bool,
+}
+
+/// Geometry of persistent windows by registry key and whether they were open at exit.
+/// Stored one window per line as tab separated
+/// `key x y width height maximized display open`, keys must not contain tabs or newlines.
+pub struct Session {
+    storage: Box<dyn SessionStorage>,
+    entries: HashMap<String, SessionEntry>,
+}
+
+impl Session {
+    /// Reads the stored session, malformed lines are skipped.
+    pub fn load<S: 'static + SessionStorage>(storage: S) -> Result<Self, String> {
+        let mut entries = HashMap::new();
+        if let Some(data) = storage.load()? {
+            for line in data.lines() {
+                if let Some((key, entry)) = parse_line(line) {
+                    entries.insert(key, entry);
+                }
+            }
+        }
+        Ok(Self {
+            storage: Box::new(storage),
+            entries,
+        })
+    }
+
+    pub fn geometry(&self, key: &str) -> Option<&WindowGeometry> {
+        self.entries.get(key).map(|e| &e.geometry)
+    }
+
+    /// Whether the window was open when the session was last saved.
+    pub fn was_open(&self, key: &str) -> bool {
+        self.entries.get(key).is_some_and(|e| e.open)
+    }
+
+    pub fn record(&mut self, key: &str, geometry: WindowGeometry, open: bool) {
+        self.entries.insert(key.to_string(), SessionEntry { geometry, open });
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let mut keys: Vec<&String> = self.entries.keys().collect();
+        keys.sort();
+        let mut data = String::new();
+        for key in keys {
+            let SessionEntry { geometry: g, open } = &self.entries[key];
+            data.push_str(&format!(
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+                key, g.x, g.y, g.width, g.height, g.maximized as u8, g.display, *open as u8
+            ));
+        }
+        self.storage.save(&data)
+    }
+}
+
+fn parse_line(line: &str) -> Option<(String, SessionEntry)> {
+    let fields: Vec<&str> = line.split('\t').collect();
+    if fields.len() != 8 || fields[0].is_empty() {
+        return None;
+    }
+    let flag = |s: &str| match s {
+        "0" => Some(false),
+        "1" => Some(true),
+        _ => None,
+    };
+    let geometry = WindowGeometry {
+        x: fields[1].parse().ok()?,
+        y: fields[2].parse().ok()?,
+        width: fields[3].parse().ok()?,
+        height: fields[4].parse().ok()?,
+        maximized: flag(fields[5])?,
+        display: fields[6].parse().ok()?,
+    };
+    let open = flag(fields[7])?;
+    Some((fields[0].to_string(), SessionEntry { geometry, open }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+
+    /// Keeps the stored session in memory, clones share it.
+    #[derive(Clone, Default)]
+    struct MemoryStorage(Rc<RefCell<Option<String>>>);
+
+    impl SessionStorage for MemoryStorage {
+        fn load(&self) -> Result<Option<String>, String> {
+            Ok(self.0.borrow().clone())
+        }
+
+        fn save(&self, data: &str) -> Result<(), String> {
+            *self.0.borrow_mut() = Some(data.to_string());
+            Ok(())
+        }
+    }
+
+    fn geometry(x: i32, y: i32, width: u32, height: u32, display: i32) -> WindowGeometry {
+        WindowGeometry {
+            x,
+            y,
+            width,
+            height,
+            maximized: false,
+            display,
+        }
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let storage = MemoryStorage::default();
+        let mut session = Session::load(storage.clone()).unwrap();
+        let main = WindowGeometry {
+            maximized: true,
+            ..geometry(-20, 30, 800, 600, 1)
+        };
+        session.record("main", main.clone(), true);
+        session.record("debug", geometry(5, 6, 400, 300, 0), false);
+        session.save().unwrap();
+        assert_eq!(
+            storage.0.borrow().as_deref(),
+            Some("debug\t5\t6\t400\t300\t0\t0\t0\nmain\t-20\t30\t800\t600\t1\t1\t1\n")
+        );
+
+        let loaded = Session::load(storage).unwrap();
+        assert_eq!(loaded.geometry("main"), Some(&main));
+        assert!(loaded.was_open("main"));
+        assert_eq!(loaded.geometry("debug"), Some(&geometry(5, 6, 400, 300, 0)));
+        assert!(!loaded.was_open("debug"));
+        assert_eq!(loaded.geometry("other"), None);
+    }
+
+    #[test]
+    fn skips_malformed_lines() {
+        let data = [
+            "ok\t1\t2\t3\t4\t0\t0\t1",
+            "",
+            "short\t1\t2\t3",
+            "long\t1\t2\t3\t4\t0\t0\t1\t9",
+            "\t1\t2\t3\t4\t0\t0\t1",
+            "number\tx\t2\t3\t4\t0\t0\t1",
+            "negative_width\t1\t2\t-3\t4\t0\t0\t1",
+            "flag\t1\t2\t3\t4\t2\t0\t1",
+        ]
+        .join("\n");
+        let session = Session::load(MemoryStorage(Rc::new(RefCell::new(Some(data))))).unwrap();
+        assert_eq!(session.entries.len(), 1);
+        assert_eq!(session.geometry("ok"), Some(&geometry(1, 2, 3, 4, 0)));
+    }
+
+    #[test]
+    fn clamps_to_displays() {
+        let displays = [Some(Rect::new(0, 0, 1920, 1080)), Some(Rect::new(1920, 0, 1280, 1024))];
+
+        // inside its display nothing changes
+        let inside = geometry(2000, 100, 800, 600, 1);
+        assert_eq!(inside.clamp_to(&displays), inside);
+        // moved back onto the display
+        assert_eq!(geometry(-100, 900, 800, 600, 0).clamp_to(&displays), geometry(0, 480, 800, 600, 0));
+        // shrunk to the display
+        assert_eq!(geometry(1900, 10, 2000, 2000, 1).clamp_to(&displays), geometry(1920, 0, 1280, 1024, 1));
+        // a display that is gone falls back to the first one
+        assert_eq!(geometry(3500, 100, 800, 600, 2).clamp_to(&displays), geometry(1120, 100, 800, 600, 0));
+        // without known bounds the geometry is kept
+        let unknown = geometry(-5000, 0, 800, 600, 0);
+        assert_eq!(unknown.clamp_to(&[None]), unknown);
+    }
+}